@@ -0,0 +1,3 @@
+//! Crate `splr-ipasir` builds the IPASIR interface in [`splr::capi`] as a `cdylib` and a
+//! `staticlib`, `libsplr_ipasir.so` and `libsplr_ipasir.a`.
+pub use splr::capi::*;
@@ -0,0 +1,208 @@
+/// implement the interface to external (theory) propagators
+use {
+    super::{AssignIF, AssignStack, PropagateIF, VarManipulateIF},
+    crate::{cdb::ClauseDBIF, state::Hook, types::*},
+};
+
+/// A theory solver working with the CDCL search, like a DPLL(T) theory or an SMT solver.
+/// Every literal is given as an `i32`, like a literal in DIMACS format.
+///
+/// ## Caveat
+/// - The vars it refers to must be frozen by [`freeze`](`crate::solver::SatSolverIF::freeze`);
+///   literals on eliminated vars are ignored, except in the clauses given by
+///   [`check_model`](`crate::assign::ExternalPropagator::check_model`), which stop the search
+///   with `SolverError::OutOfRange`.
+/// - Certification isn't available because theory clauses can't be verified.
+pub trait ExternalPropagator {
+    /// receive the literals assigned since the last call, in the order of assignment.
+    fn notify_assignment(&mut self, _lits: &[i32]) {}
+    /// receive the opening of a new decision level.
+    fn notify_new_decision_level(&mut self) {}
+    /// receive a backjump; every assignment above decision level `level` is undone.
+    /// Assignments at lower levels made after the opening of `level + 1` are notified again.
+    fn notify_backtrack(&mut self, _level: usize) {}
+    /// return the literals implied by the theory under the current assignment.
+    /// Their reasons are requested lazily by [`reason`](`crate::assign::ExternalPropagator::reason`).
+    fn propagate(&mut self) -> Vec<i32> {
+        Vec::new()
+    }
+    /// return a reason clause of `lit`, which was returned by
+    /// [`propagate`](`crate::assign::ExternalPropagator::propagate`).
+    /// It contains `lit`, and all other literals in it must be false now.
+    fn reason(&mut self, lit: i32) -> Vec<i32>;
+    /// return a literal to be decided next, or `None` to leave it to the solver.
+    fn decide(&mut self) -> Option<i32> {
+        None
+    }
+    /// check a complete assignment, which consists of the assigned literals.
+    /// Return the empty vector to accept it, or clauses falsified by it to reject it.
+    fn check_model(&mut self, _model: &[i32]) -> Vec<Vec<i32>> {
+        Vec::new()
+    }
+}
+
+/// API to drive an external propagator from the search.
+pub trait ExternalPropagateIF {
+    /// connect an external propagator; `None` disconnects the current one.
+    fn connect_propagator(&mut self, propagator: Option<Box<dyn ExternalPropagator + Send>>);
+    /// return `true` if an external propagator is connected.
+    fn has_propagator(&self) -> bool;
+    /// replace the pending reasons of literals implied by the external propagator with
+    /// clauses. Call it before conflict analysis.
+    fn explain_external<C>(&mut self, cdb: &mut C)
+    where
+        C: ClauseDBIF;
+    /// return a decision literal suggested by the external propagator.
+    fn decide_external(&mut self) -> Option<Lit>;
+    /// return the clauses which reject the current complete assignment.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::OutOfRange` if a clause has a literal on an unknown or eliminated var;
+    ///   the clause without it can't reject the assignment.
+    fn check_model_external(&mut self) -> Result<Vec<Vec<i32>>, SolverError>;
+}
+
+impl ExternalPropagateIF for AssignStack {
+    fn connect_propagator(&mut self, propagator: Option<Box<dyn ExternalPropagator + Send>>) {
+        self.external = Hook::new(propagator);
+        self.external_head = 0;
+        self.external_pending.clear();
+    }
+    fn has_propagator(&self) -> bool {
+        self.external.is_some()
+    }
+    fn explain_external<C>(&mut self, cdb: &mut C)
+    where
+        C: ClauseDBIF,
+    {
+        for l in std::mem::take(&mut self.external_pending) {
+            debug_assert_eq!(self.assigned(l), Some(true));
+            if let Some(cid) = self.external_clause(cdb, l) {
+                let c = &cdb[cid];
+                let reason = if c.lits.len() == 2 {
+                    c.lits[1]
+                } else {
+                    NULL_LIT
+                };
+                self.reason[l.vi()] = AssignReason::Implication(cid, reason);
+            }
+        }
+    }
+    fn decide_external(&mut self) -> Option<Lit> {
+        let i = self.external.call(|p| p.decide())??;
+        let l = self.external_lit(i)?;
+        if self.assigned(l).is_none() {
+            Some(l)
+        } else {
+            None
+        }
+    }
+    fn check_model_external(&mut self) -> Result<Vec<Vec<i32>>, SolverError> {
+        let model = self.trail.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
+        let lemmas = self
+            .external
+            .call(|p| p.check_model(&model))
+            .unwrap_or_default();
+        if lemmas
+            .iter()
+            .any(|c| c.iter().any(|i| self.external_lit(*i).is_none()))
+        {
+            return Err(SolverError::OutOfRange);
+        }
+        Ok(lemmas)
+    }
+}
+
+impl AssignStack {
+    /// pass the new assignments to the external propagator, then assign the literals
+    /// implied by it. Return a conflicting clause if it implies a falsified literal.
+    pub(super) fn propagate_external<C>(&mut self, cdb: &mut C) -> Option<ClauseId>
+    where
+        C: ClauseDBIF,
+    {
+        let lits = self.trail[self.external_head..]
+            .iter()
+            .map(|l| i32::from(*l))
+            .collect::<Vec<_>>();
+        self.external_head = self.trail.len();
+        let implied = self
+            .external
+            .call(|p| {
+                if !lits.is_empty() {
+                    p.notify_assignment(&lits);
+                }
+                p.propagate()
+            })
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|i| self.external_lit(*i))
+            .collect::<Vec<_>>();
+        let lv = self.decision_level();
+        for l in implied {
+            match self.assigned(l) {
+                Some(true) => (),
+                Some(false) => {
+                    // A conflict at the root level is left to the final model check.
+                    if let Some(cid) = self.external_clause(cdb, l) {
+                        self.last_conflict = l.vi();
+                        self.num_conflict += 1;
+                        return Some(cid);
+                    }
+                }
+                None => {
+                    self.assign_by_implication(l, AssignReason::default(), lv);
+                    // Reasons of assignments at the root level are never used.
+                    if 0 < lv {
+                        self.external_pending.push(l);
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// convert a literal given by the external propagator; reject invalid ones.
+    fn external_lit(&self, i: i32) -> Option<Lit> {
+        let vi = i.unsigned_abs() as usize;
+        if i == 0 || self.num_vars < vi || self.var[vi].is(Flag::ELIMINATED) {
+            return None;
+        }
+        Some(Lit::from(i))
+    }
+    /// add the reason clause of `l` given by the external propagator. `l` is placed first,
+    /// and the others follow in descending order of level to be watched correctly.
+    /// A unit reason is weakened by the decision at its level.
+    /// Return `None` if the clause has no literal above the root level.
+    fn external_clause<C>(&mut self, cdb: &mut C, l: Lit) -> Option<ClauseId>
+    where
+        C: ClauseDBIF,
+    {
+        let reason = self
+            .external
+            .call(|p| p.reason(i32::from(l)))
+            .unwrap_or_default();
+        let mut lits = vec![l];
+        for k in reason.iter().filter_map(|i| self.external_lit(*i)) {
+            if !lits.contains(&k) {
+                debug_assert_eq!(self.assigned(k), Some(false));
+                lits.push(k);
+            }
+        }
+        // A falsified `l` makes a conflicting clause, which is sorted entirely.
+        let from = if self.assigned(l) == Some(true) { 1 } else { 0 };
+        let level = &self.level;
+        lits[from..].sort_unstable_by_key(|k| std::cmp::Reverse(level[k.vi()]));
+        let lv = lits.iter().map(|k| level[k.vi()]).max().unwrap_or(0);
+        if lv == 0 {
+            return None;
+        }
+        if lits.len() == 1 {
+            let decision = self.trail[self.trail_lim[lv as usize - 1]];
+            if decision.vi() == l.vi() {
+                return None;
+            }
+            lits.push(!decision);
+        }
+        Some(cdb.new_clause(self, &mut lits, true, false, &[]))
+    }
+}
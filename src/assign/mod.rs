@@ -1,11 +1,15 @@
+/// Var rewarding based on EVSIDS
+mod evsids;
+/// external propagators
+mod external;
 /// Crate `assign` implements Boolean Constraint Propagation and decision var selection.
 /// This version can handle Chronological and Non Chronological Backtrack.
 mod heap;
+/// Var rewarding based on Learning Rate
+mod learning_rate;
 /// Boolean constraint propagation
 mod propagate;
 /// Var rewarding
-#[cfg_attr(feature = "EVSIDS", path = "evsids.rs")]
-#[cfg_attr(not(feature = "EVSIDS"), path = "learning_rate.rs")]
 mod reward;
 /// Decision var selection
 mod select;
@@ -13,14 +17,23 @@ mod select;
 mod stack;
 /// var struct and its methods
 mod var;
+/// Var queue for VMTF
+mod vmtf;
 
 pub use self::{
-    propagate::PropagateIF, select::VarSelectIF, stack::ClauseManipulateIF, var::VarManipulateIF,
+    external::{ExternalPropagateIF, ExternalPropagator},
+    propagate::PropagateIF,
+    select::{RephaseTarget, VarSelectIF},
+    stack::ClauseManipulateIF,
+    var::VarManipulateIF,
 };
 
 use {
-    self::heap::{VarHeapIF, VarOrderIF},
-    super::{cdb::ClauseDBIF, types::*},
+    self::{
+        heap::{VarHeapIF, VarOrderIF},
+        vmtf::VarQueueIF,
+    },
+    super::{cdb::ClauseDBIF, state::Hook, types::*},
     std::{collections::HashMap, ops::Range, slice::Iter},
 };
 
@@ -40,9 +53,6 @@ pub trait VarRewardIF {
     fn reward_at_unassign(&mut self, vi: VarId);
     /// update internal counter.
     fn reward_update(&mut self);
-    #[cfg(feature = "moving_var_reward_rate")]
-    /// update reward setting as a part of module adaptation.
-    fn adjust_reward(&mut self, state: &State);
 }
 
 /// API about assignment like [`decision_level`](`crate::assign::AssignIF::decision_level`), [`stack`](`crate::assign::AssignIF::stack`), [`best_assigned`](`crate::assign::AssignIF::best_assigned`), and so on.
@@ -127,7 +137,14 @@ pub struct AssignStack {
     q_head: usize,
     pub root_level: DecisionLevel,
     last_conflict: VarId,
-    var_order: VarIdHeap, // Variable Order
+    var_order: VarIdHeap,  // Variable Order
+    var_queue: VarIdQueue, // Variable Order for VMTF
+    /// `true` to select decision vars by VMTF in focused mode
+    use_vmtf: bool,
+    /// `true` to select decision vars by VMTF also in stabilization mode
+    use_vmtf_in_stable_mode: bool,
+    /// `true` during a stabilization phase
+    stabilizing: bool,
 
     //
     //## Phase handling
@@ -138,6 +155,26 @@ pub struct AssignStack {
     build_best_at: usize,
     num_best_assign: usize,
     rephasing: bool,
+    /// the cycle of rephasing targets
+    rephase_cycle: Vec<RephaseTarget>,
+    /// the unit of the interval between rephasings, which grows arithmetically
+    rephase_int: usize,
+    /// the number of conflicts at which the next rephasing occurs
+    next_rephase: usize,
+    /// the number of rephasings
+    num_rephase: usize,
+    /// the last rephasing target
+    rephase_target: RephaseTarget,
+    /// the phases found by the last local search for `RephaseTarget::Walk`
+    walk_phases: HashMap<VarId, bool>,
+    /// `true` to use target phases in stabilization mode
+    use_target_phases: bool,
+    /// `true` to use target phases also in focused mode
+    use_target_phases_in_focused_mode: bool,
+    target_assign: bool,
+    /// the phases on the longest conflict-free trail in the current or the last stable phase
+    target_phases: HashMap<VarId, bool>,
+    num_target_assign: usize,
 
     //
     //## Stage handling
@@ -175,17 +212,15 @@ pub struct AssignStack {
     //
     //## Var Rewarding
     //
+    /// `true` for EVSIDS, `false` for LR
+    use_evsids: bool,
+    /// `true` to increase var activity decay in LR
+    use_moving_var_reward_rate: bool,
     /// var activity decay
     activity_decay: f64,
-
-    #[cfg(feature = "moving_var_reward_rate")]
     /// maximum var activity decay
     activity_decay_max: f64,
-    #[cfg(feature = "moving_var_reward_rate")]
-    /// minimum var activity decay
-    activity_decay_min: f64,
-    #[cfg(feature = "moving_var_reward_rate")]
-    /// ONLY used in feature EVSIDS
+    /// the increment of var reward in EVSIDS, or of activity decay in LR
     reward_step: f64,
 
     /// for LR
@@ -196,6 +231,18 @@ pub struct AssignStack {
     //
     /// save old num_conflict, num_propagation, num_restart
     vivify_sandbox: (usize, usize, usize),
+    /// `true` during vivification, which is hidden from the external propagator
+    during_vivification: bool,
+
+    //
+    //## External propagation
+    //
+    /// an external propagator
+    external: Hook<Box<dyn ExternalPropagator + Send>>,
+    /// the number of assignments on the trail notified to the external propagator
+    external_head: usize,
+    /// literals implied by the external propagator and waiting for their reasons
+    external_pending: Vec<Lit>,
 }
 
 /// Heap of VarId, based on var activity.
@@ -211,3 +258,25 @@ pub struct VarIdHeap {
     /// `idxs[0]` holds the number of alive elements
     idxs: Vec<usize>,
 }
+
+/// Var queue for VMTF, linked from the least recently bumped var to the most recently bumped one.
+/// `0` stands for no var.
+#[derive(Clone, Debug, Default)]
+pub struct VarIdQueue {
+    /// the less recently bumped neighbor of each var
+    prev: Vec<VarId>,
+    /// the more recently bumped neighbor of each var
+    next: Vec<VarId>,
+    /// the time each var was bumped lastly
+    stamp: Vec<usize>,
+    /// the least recently bumped var
+    first: VarId,
+    /// the most recently bumped var
+    last: VarId,
+    /// the var to start searching from; all vars bumped after it are assigned.
+    search: VarId,
+    /// the number of bumps
+    counter: usize,
+    /// vars analyzed in conflicts, to be bumped at the next backjump
+    analyzed: Vec<VarId>,
+}
@@ -1,7 +1,7 @@
 /// implement boolean constraint propagation, backjump
 /// This version can handle Chronological and Non Chronological Backtrack.
 use {
-    super::{AssignIF, AssignStack, VarHeapIF, VarRewardIF, VarSelectIF},
+    super::{AssignIF, AssignStack, VarHeapIF, VarQueueIF, VarRewardIF, VarSelectIF},
     crate::{
         cdb::{ClauseDBIF, WatchDBIF},
         types::*,
@@ -30,6 +30,9 @@ pub trait PropagateIF {
     /// - Callers have to assure the consistency after this assignment.
     /// - No need to restart; but execute `propagate` just afterward.
     fn assign_by_unitclause(&mut self, l: Lit);
+    /// open a new decision level without any assignment.
+    /// It is used for an assumption which has been satisfied already.
+    fn level_up(&mut self);
     /// execute *backjump*.
     fn cancel_until(&mut self, lv: DecisionLevel);
     /// execute *boolean constraint propagation* or *unit propagation*.
@@ -155,7 +158,16 @@ impl PropagateIF for AssignStack {
         // NOTE: synchronize the following with handle(SolverEvent::Assert)
         self.make_var_asserted(vi);
     }
+    fn level_up(&mut self) {
+        self.trail_lim.push(self.trail.len());
+        if !self.during_vivification {
+            self.external.call(|p| p.notify_new_decision_level());
+        }
+    }
     fn cancel_until(&mut self, lv: DecisionLevel) {
+        if self.use_vmtf {
+            self.bump_analyzed_vars();
+        }
         if self.trail_lim.len() as u32 <= lv {
             return;
         }
@@ -163,6 +175,10 @@ impl PropagateIF for AssignStack {
             self.save_best_phases();
             self.best_assign = false;
         }
+        if self.target_assign {
+            self.save_target_phases();
+            self.target_assign = false;
+        }
         let lim = self.trail_lim[lv as usize];
         let mut shift = lim;
         for i in lim..self.trail.len() {
@@ -179,6 +195,9 @@ impl PropagateIF for AssignStack {
             self.reason[vi] = AssignReason::default();
             self.reward_at_unassign(vi);
             self.insert_heap(vi);
+            if self.use_vmtf {
+                self.update_queue(vi);
+            }
         }
         self.trail.truncate(shift);
         debug_assert!(self
@@ -187,12 +206,40 @@ impl PropagateIF for AssignStack {
             .all(|l| var_assign!(self, l.vi()).is_some()));
         debug_assert!(self.trail.iter().all(|k| !self.trail.contains(&!*k)));
         self.trail_lim.truncate(lv as usize);
+        // Assignments kept by chronoBT are notified again to the external propagator.
+        self.external_head = self.external_head.min(lim);
+        if self.external.is_some() {
+            let level = &self.level;
+            self.external_pending.retain(|l| level[l.vi()] <= lv);
+            if !self.during_vivification {
+                self.external.call(|p| p.notify_backtrack(lv as usize));
+            }
+        }
         // assert!(lim < self.q_head) doesn't hold sometimes in chronoBT.
         self.q_head = self.q_head.min(lim);
         if lv == self.root_level {
             self.num_restart += 1;
         }
     }
+    fn propagate<C>(&mut self, cdb: &mut C) -> ClauseId
+    where
+        C: ClauseDBIF,
+    {
+        loop {
+            let cc = self.propagate_clauses(cdb);
+            if !cc.is_none() || !self.external.is_some() || self.during_vivification {
+                return cc;
+            }
+            match self.propagate_external(cdb) {
+                Some(cc) => return cc,
+                None if self.remains() => (),
+                None => return cc,
+            }
+        }
+    }
+}
+
+impl AssignStack {
     /// UNIT PROPAGATION.
     /// Note:
     ///  - *Precondition*: no checking dead clauses. They cause crash.
@@ -200,7 +247,7 @@ impl PropagateIF for AssignStack {
     ///    So Eliminator should call `garbage_collect` before me.
     ///  - The order of literals in binary clauses will be modified to hold
     ///    propagation order.
-    fn propagate<C>(&mut self, cdb: &mut C) -> ClauseId
+    fn propagate_clauses<C>(&mut self, cdb: &mut C) -> ClauseId
     where
         C: ClauseDBIF,
     {
@@ -229,11 +276,15 @@ impl PropagateIF for AssignStack {
                             return w.c;
                         }
                         None => {
+                            let lv = self.level[false_lit.vi()];
                             self.assign_by_implication(
                                 w.blocker,
                                 AssignReason::Implication(w.c, false_lit),
-                                self.level[false_lit.vi()],
+                                lv,
                             );
+                            if lv == 0 {
+                                cdb.certificate_implied_unit(w.blocker, w.c);
+                            }
                         }
                     }
                 }
@@ -296,6 +347,9 @@ impl PropagateIF for AssignStack {
                         .max()
                         .unwrap_or(0);
                     self.assign_by_implication(first, AssignReason::Implication(w.c, NULL_LIT), lv);
+                    if lv == 0 {
+                        cdb.certificate_implied_unit(first, w.c);
+                    }
                 }
             }
         }
@@ -304,14 +358,16 @@ impl PropagateIF for AssignStack {
             self.best_assign = true;
             self.num_best_assign = na;
         }
+        if self.use_target_phases
+            && self.stabilizing
+            && self.num_target_assign < na
+            && 0 < self.decision_level()
+        {
+            self.target_assign = true;
+            self.num_target_assign = na;
+        }
         ClauseId::default()
     }
-}
-
-impl AssignStack {
-    fn level_up(&mut self) {
-        self.trail_lim.push(self.trail.len());
-    }
     /// save the current assignments as the best phases
     fn save_best_phases(&mut self) {
         for l in self.trail.iter().skip(self.len_upto(0)) {
@@ -322,4 +378,13 @@ impl AssignStack {
         }
         self.build_best_at = self.num_propagation;
     }
+    /// save the current assignments as the target phases
+    fn save_target_phases(&mut self) {
+        for l in self.trail.iter().skip(self.len_upto(0)) {
+            let vi = l.vi();
+            if let Some(b) = self.assign[vi] {
+                self.target_phases.insert(vi, b);
+            }
+        }
+    }
 }
@@ -0,0 +1,83 @@
+/// Var Rewarding dispatched to EVSIDS or Learning Rate at runtime
+use {
+    super::{evsids, learning_rate, AssignStack, VarRewardIF},
+    crate::types::*,
+    std::slice::Iter,
+};
+
+impl VarRewardIF for AssignStack {
+    #[inline]
+    fn activity(&self, vi: VarId) -> f64 {
+        if self.use_evsids {
+            evsids::activity(self, vi)
+        } else {
+            learning_rate::activity(self, vi)
+        }
+    }
+    fn initialize_reward(&mut self, _iterator: Iter<'_, usize>) {
+        if self.use_evsids {
+            evsids::initialize_reward(self);
+        } else {
+            learning_rate::initialize_reward(self);
+        }
+    }
+    fn clear_reward(&mut self, vi: VarId) {
+        self.var[vi].reward = 0.0;
+    }
+    #[inline]
+    fn reward_at_analysis(&mut self, vi: VarId) {
+        if self.use_vmtf {
+            self.var_queue.analyzed.push(vi);
+        }
+        if self.use_evsids {
+            evsids::reward_at_analysis(self, vi);
+        } else {
+            learning_rate::reward_at_analysis(self, vi);
+        }
+    }
+    #[inline]
+    fn reward_at_assign(&mut self, vi: VarId) {
+        if !self.use_evsids {
+            learning_rate::reward_at_assign(self, vi);
+        }
+    }
+    #[inline]
+    fn reward_at_unassign(&mut self, vi: VarId) {
+        if !self.use_evsids {
+            learning_rate::reward_at_unassign(self, vi);
+        }
+    }
+    fn reward_update(&mut self) {
+        if self.use_evsids {
+            evsids::reward_update(self);
+        } else {
+            learning_rate::reward_update(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::Config,
+        solver::{Certificate, SatSolverIF, SolveIF, Solver},
+    };
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_reward_schemes() {
+        for (evsids, mvr) in &[(0, 0), (0, 1), (1, 0), (1, 1)] {
+            for (cnf, sat) in &[("tests/uf100-010.cnf", true), ("tests/unsat.cnf", false)] {
+                let mut config = Config::from(*cnf);
+                config.inject_from(&["--EVS", &evsids.to_string(), "--MVR", &mvr.to_string()]);
+                let mut s = Solver::build(&config).expect("can't load");
+                assert_eq!(s.asg.use_evsids, *evsids == 1);
+                assert_eq!(s.asg.use_moving_var_reward_rate, *mvr == 1);
+                match s.solve() {
+                    Ok(Certificate::SAT(_)) => assert!(*sat),
+                    Ok(Certificate::UNSAT) => assert!(!*sat),
+                    _ => panic!("failed to solve {}", cnf),
+                }
+            }
+        }
+    }
+}
@@ -2,8 +2,9 @@
 use crate::state::StagingTarget;
 /// Decision var selection
 use {
-    super::{AssignStack, Var, VarHeapIF, VarOrderIF, VarRewardIF},
+    super::{AssignStack, Var, VarHeapIF, VarOrderIF, VarQueueIF, VarRewardIF},
     crate::types::*,
+    std::{collections::HashMap, convert::TryFrom, fmt},
 };
 
 /// ```
@@ -37,6 +38,67 @@ pub trait VarSelectIF {
     fn rebuild_order(&mut self);
     /// make a var asserted.
     fn make_var_asserted(&mut self, vi: VarId);
+    /// return a reference to the best phases.
+    fn best_phases_ref(&self) -> &HashMap<VarId, bool>;
+    /// set the phases for `RephaseTarget::Walk` to `phases`. The best phases are kept.
+    fn set_walk_phases(&mut self, phases: HashMap<VarId, bool>);
+    /// return the next target in the rephase cycle if it's time to rephase.
+    fn next_rephase_target(&mut self) -> Option<RephaseTarget>;
+    /// overwrite the saved phases by `target` and reset the target phases.
+    /// The phases for `RephaseTarget::Walk` should be given by `set_walk_phases`.
+    fn rephase(&mut self, target: RephaseTarget);
+    /// return the following data:
+    /// * the last rephasing target
+    /// * the number of rephasings
+    /// * the number of vars in the target phases
+    /// * the number of vars in the best phases
+    fn phase_stats(&self) -> (RephaseTarget, usize, usize, usize);
+}
+
+/// Phases to overwrite the saved phases with at rephasing
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RephaseTarget {
+    /// the best phases found so far
+    Best,
+    /// the negation of the saved phases
+    Flipping,
+    /// the negation of the original phase
+    Inverted,
+    /// the phase used at the beginning: `false`
+    Original,
+    /// random phases
+    Random,
+    /// the phases found by stochastic local search
+    Walk,
+}
+
+impl fmt::Display for RephaseTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            RephaseTarget::Best => "best",
+            RephaseTarget::Flipping => "flipping",
+            RephaseTarget::Inverted => "inverted",
+            RephaseTarget::Original => "original",
+            RephaseTarget::Random => "random",
+            RephaseTarget::Walk => "walk",
+        })
+    }
+}
+
+impl TryFrom<char> for RephaseTarget {
+    type Error = SolverError;
+    /// convert a letter in `--rpc` to a target.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'B' => Ok(RephaseTarget::Best),
+            'F' => Ok(RephaseTarget::Flipping),
+            'I' => Ok(RephaseTarget::Inverted),
+            'O' => Ok(RephaseTarget::Original),
+            'R' => Ok(RephaseTarget::Random),
+            'W' => Ok(RephaseTarget::Walk),
+            _ => Err(SolverError::OutOfRange),
+        }
+    }
 }
 
 #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -186,6 +248,11 @@ impl VarSelectIF for AssignStack {
                 return Lit::from_assign(vi, *b);
             }
         }
+        if self.use_target_phases && (self.use_target_phases_in_focused_mode || self.stabilizing) {
+            if let Some(b) = self.target_phases.get(&vi) {
+                return Lit::from_assign(vi, *b);
+            }
+        }
         Lit::from_assign(vi, self.var[vi].is(Flag::PHASE))
     }
     fn update_order(&mut self, v: VarId) {
@@ -198,6 +265,7 @@ impl VarSelectIF for AssignStack {
                 self.insert_heap(vi);
             }
         }
+        self.reset_queue();
     }
     fn make_var_asserted(&mut self, vi: VarId) {
         self.num_asserted_vars += 1;
@@ -205,6 +273,59 @@ impl VarSelectIF for AssignStack {
         self.remove_from_heap(vi);
         self.check_best_phase(vi);
     }
+    fn best_phases_ref(&self) -> &HashMap<VarId, bool> {
+        &self.best_phases
+    }
+    fn set_walk_phases(&mut self, phases: HashMap<VarId, bool>) {
+        self.walk_phases = phases;
+    }
+    fn next_rephase_target(&mut self) -> Option<RephaseTarget> {
+        if !self.use_rephase
+            || self.rephase_cycle.is_empty()
+            || self.num_conflict < self.next_rephase
+        {
+            return None;
+        }
+        let target = self.rephase_cycle[self.num_rephase % self.rephase_cycle.len()];
+        self.num_rephase += 1;
+        self.next_rephase = self.num_conflict + self.rephase_int * (self.num_rephase + 1);
+        self.rephase_target = target;
+        Some(target)
+    }
+    fn rephase(&mut self, target: RephaseTarget) {
+        let mut rng = Xorshift::new((self.num_conflict + self.num_rephase) as u64);
+        for v in self.var.iter_mut().skip(1) {
+            if v.is(Flag::ELIMINATED) {
+                continue;
+            }
+            match target {
+                RephaseTarget::Best => {
+                    if let Some(b) = self.best_phases.get(&v.index) {
+                        v.set(Flag::PHASE, *b);
+                    }
+                }
+                RephaseTarget::Flipping => v.set(Flag::PHASE, !v.is(Flag::PHASE)),
+                RephaseTarget::Inverted => v.turn_on(Flag::PHASE),
+                RephaseTarget::Original => v.turn_off(Flag::PHASE),
+                RephaseTarget::Random => v.set(Flag::PHASE, rng.next_bool()),
+                RephaseTarget::Walk => {
+                    if let Some(b) = self.walk_phases.get(&v.index) {
+                        v.set(Flag::PHASE, *b);
+                    }
+                }
+            }
+        }
+        self.target_phases.clear();
+        self.num_target_assign = 0;
+    }
+    fn phase_stats(&self) -> (RephaseTarget, usize, usize, usize) {
+        (
+            self.rephase_target,
+            self.num_rephase,
+            self.num_target_assign,
+            self.num_best_assign,
+        )
+    }
 }
 
 impl AssignStack {
@@ -229,6 +350,9 @@ impl AssignStack {
     }
     /// select a decision var
     fn select_var(&mut self) -> VarId {
+        if self.use_vmtf && (self.use_vmtf_in_stable_mode || !self.stabilizing) {
+            return self.select_queue_var();
+        }
         loop {
             let vi = self.get_heap_root();
             if var_assign!(self, vi).is_none() && !self.var[vi].is(Flag::ELIMINATED) {
@@ -237,3 +361,61 @@ impl AssignStack {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::SolverEvent;
+
+    #[test]
+    fn test_rephase_cycle() {
+        let mut config = Config::default();
+        config.rph_cycle = "BFOI".to_string();
+        config.rph_int = 10;
+        let cnf = CNFDescription {
+            num_of_variables: 4,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let phases = |asg: &AssignStack| {
+            (1..=4)
+                .map(|vi| asg.var[vi].is(Flag::PHASE))
+                .collect::<Vec<_>>()
+        };
+        asg.best_phases.insert(1, true);
+        asg.best_phases.insert(3, true);
+        assert_eq!(asg.next_rephase_target(), None);
+        let mut targets = Vec::new();
+        for n in 0..100 {
+            asg.num_conflict = n;
+            if let Some(target) = asg.next_rephase_target() {
+                asg.rephase(target);
+                targets.push((n, target, phases(&asg)));
+            }
+        }
+        assert_eq!(
+            targets,
+            vec![
+                (10, RephaseTarget::Best, vec![true, false, true, false]),
+                (30, RephaseTarget::Flipping, vec![false, true, false, true]),
+                (60, RephaseTarget::Original, vec![false; 4]),
+            ]
+        );
+        asg.num_conflict = 100;
+        assert_eq!(asg.next_rephase_target(), Some(RephaseTarget::Inverted));
+        assert_eq!(asg.phase_stats().1, 4);
+        let mut walk = HashMap::new();
+        walk.insert(2, true);
+        asg.set_walk_phases(walk);
+        asg.rephase(RephaseTarget::Walk);
+        assert_eq!(phases(&asg), vec![false, true, false, false]);
+        assert_eq!(asg.best_phases.len(), 2);
+        // Target phases are reset at the beginning of a stable phase, and kept after it.
+        asg.target_phases.insert(4, true);
+        asg.handle(SolverEvent::Stabilize((false, true)));
+        assert_eq!(asg.target_phases.len(), 1);
+        asg.handle(SolverEvent::Stabilize((false, false)));
+        assert!(asg.target_phases.is_empty());
+        assert!(RephaseTarget::try_from('X').is_err());
+    }
+}
@@ -1,8 +1,11 @@
 /// main struct AssignStack
 use {
-    super::{AssignIF, AssignStack, Var, VarIdHeap, VarManipulateIF, VarOrderIF, VarSelectIF},
-    crate::{cdb::ClauseDBIF, solver::SolverEvent, types::*},
-    std::{collections::HashMap, fmt, ops::Range, slice::Iter},
+    super::{
+        AssignIF, AssignStack, RephaseTarget, Var, VarHeapIF, VarIdHeap, VarIdQueue,
+        VarManipulateIF, VarOrderIF, VarSelectIF,
+    },
+    crate::{cdb::ClauseDBIF, solver::SolverEvent, state::Hook, types::*},
+    std::{collections::HashMap, convert::TryFrom, fmt, ops::Range, slice::Iter},
 };
 
 #[cfg(not(feature = "no_IO"))]
@@ -47,6 +50,10 @@ impl Default for AssignStack {
             root_level: 0,
             last_conflict: VarId::default(),
             var_order: VarIdHeap::default(),
+            var_queue: VarIdQueue::default(),
+            use_vmtf: false,
+            use_vmtf_in_stable_mode: false,
+            stabilizing: false,
 
             use_rephase: true,
             best_assign: false,
@@ -54,6 +61,17 @@ impl Default for AssignStack {
             build_best_at: 0,
             num_best_assign: 0,
             rephasing: false,
+            rephase_cycle: Vec::new(),
+            rephase_int: 1000,
+            next_rephase: 1000,
+            num_rephase: 0,
+            rephase_target: RephaseTarget::Original,
+            walk_phases: HashMap::new(),
+            use_target_phases: false,
+            use_target_phases_in_focused_mode: false,
+            target_assign: false,
+            target_phases: HashMap::new(),
+            num_target_assign: 0,
 
             use_stage: true,
             staging_reward_value: 1.0,
@@ -72,18 +90,20 @@ impl Default for AssignStack {
             ordinal: 0,
             var: Vec::new(),
 
+            use_evsids: false,
+            use_moving_var_reward_rate: false,
             activity_decay: 0.0,
-
-            #[cfg(feature = "moving_var_reward_rate")]
             activity_decay_max: 0.9,
-            #[cfg(feature = "moving_var_reward_rate")]
-            activity_decay_min: 0.8,
-            #[cfg(feature = "moving_var_reward_rate")]
             reward_step: 0.0,
 
             occurrence_compression_rate: 0.5,
 
             vivify_sandbox: (0, 0, 0),
+            during_vivification: false,
+
+            external: Hook::default(),
+            external_head: 0,
+            external_pending: Vec::new(),
         }
     }
 }
@@ -111,20 +131,33 @@ impl Instantiate for AssignStack {
             reason: vec![AssignReason::default(); nv + 1],
             trail: Vec::with_capacity(nv),
             var_order: VarIdHeap::new(nv, nv),
+            var_queue: VarIdQueue::new(nv),
+            use_vmtf: config.use_vmtf(),
+            use_vmtf_in_stable_mode: config.use_vmtf_in_stable_mode(),
             use_rephase: config.use_rephase(),
+            rephase_cycle: config
+                .rph_cycle
+                .chars()
+                .filter_map(|c| RephaseTarget::try_from(c).ok())
+                .filter(|t| *t != RephaseTarget::Walk || config.use_sls())
+                .collect(),
+            rephase_int: config.rph_int,
+            next_rephase: config.rph_int,
+            use_target_phases: config.use_target_phases(),
+            use_target_phases_in_focused_mode: config.use_target_phases_in_focused_mode(),
             use_stage: config.use_stage(),
             staging_reward_decay: config.stg_rwd_dcy,
             staging_reward_value: config.stg_rwd_val,
             num_vars: cnf.num_of_variables,
             var: Var::new_vars(nv),
-            #[cfg(not(feature = "moving_var_reward_rate"))]
-            activity_decay: config.vrw_dcy_rat,
-            #[cfg(feature = "moving_var_reward_rate")]
-            activity_decay: config.vrw_dcy_beg,
-            #[cfg(feature = "moving_var_reward_rate")]
+            use_evsids: config.use_evsids(),
+            use_moving_var_reward_rate: config.use_moving_var_reward_rate(),
+            activity_decay: if config.use_moving_var_reward_rate() {
+                config.vrw_dcy_beg
+            } else {
+                config.vrw_dcy_rat
+            },
             activity_decay_max: config.vrw_dcy_end,
-            #[cfg(feature = "moving_var_reward_rate")]
-            activity_decay_min: config.vrw_dcy_beg,
             occurrence_compression_rate: config.vrw_occ_cmp,
             ..AssignStack::default()
         }
@@ -142,11 +175,13 @@ impl Instantiate for AssignStack {
                 self.assign.push(None);
                 self.level.push(DecisionLevel::default());
                 self.reason.push(AssignReason::default());
-                self.var_order.heap.push(0);
-                self.var_order.idxs.push(0);
-                self.var_order.clear();
                 self.num_vars += 1;
                 self.var.push(Var::from(self.num_vars));
+                // put the new var at the tail, out of the heap, then insert it.
+                self.var_order.heap.push(self.num_vars);
+                self.var_order.idxs.push(self.num_vars);
+                self.insert_heap(self.num_vars);
+                self.var_queue.push(self.num_vars);
             }
             SolverEvent::Reinitialize => {
                 debug_assert_eq!(self.decision_level(), self.root_level);
@@ -160,7 +195,17 @@ impl Instantiate for AssignStack {
                 };
                 self.rebuild_order();
             }
+            // A new cycle begins with a focused phase.
+            // The target phases of a stable phase are kept for the next focused phase.
+            SolverEvent::Stabilize((_, new_cycle)) => {
+                self.stabilizing = !new_cycle;
+                if self.stabilizing {
+                    self.target_phases.clear();
+                    self.num_target_assign = 0;
+                }
+            }
             SolverEvent::Vivify(start) => {
+                self.during_vivification = start;
                 if start {
                     self.vivify_sandbox =
                         (self.num_conflict, self.num_propagation, self.num_restart);
@@ -273,47 +318,15 @@ impl AssignIF for AssignStack {
                 break;
             }
             i -= 1;
-            #[cfg(feature = "incremental_solver")]
-            let mut phantom_clause = Vec::new();
             loop {
                 if width <= 1 {
                     break;
                 }
                 let l = lits[i];
-                #[cfg(feature = "incremental_solver")]
-                {
-                    phantom_clause.push(l);
-                }
                 if extended_model[l.vi()] != Some(!bool::from(l)) {
                     if i < width {
-                        #[cfg(feature = "incremental_solver")]
-                        {
-                            for l in &lits[..i] {
-                                phantom_clause.push(*l);
-                            }
-                            debug_assert!(1 < phantom_clause.len());
-                            #[cfg(feature = "trace_elimination")]
-                            println!(
-                                "- pull back clause E {:?}",
-                                phantom_clause.iter().map(i32::from).collect::<Vec<_>>()
-                            );
-                            cdb.new_clause(self, &mut phantom_clause, false, false);
-                        }
                         break 'next;
                     }
-                    #[cfg(feature = "incremental_solver")]
-                    {
-                        for l in &lits[i - width + 1..i] {
-                            phantom_clause.push(*l);
-                        }
-                        debug_assert!(1 < phantom_clause.len());
-                        #[cfg(feature = "trace_elimination")]
-                        println!(
-                            "- pull back clause C {:?}",
-                            phantom_clause.iter().map(i32::from).collect::<Vec<_>>()
-                        );
-                        cdb.new_clause(self, &mut phantom_clause, false, false);
-                    }
                     i -= width;
                     continue 'next;
                 }
@@ -322,7 +335,6 @@ impl AssignIF for AssignStack {
             }
             debug_assert!(width == 1);
             let l = lits[i];
-            debug_assert_ne!(Some(bool::from(l)), self.assigned(l));
             #[cfg(feature = "trace_elimination")]
             println!(" - extend {}", l);
             extended_model[l.vi()] = Some(bool::from(l));
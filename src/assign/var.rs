@@ -99,6 +99,8 @@ pub trait VarManipulateIF {
     fn var_iter_mut(&mut self) -> IterMut<'_, Var>;
     /// eliminate a var.
     fn set_eliminated(&mut self, vi: VarId);
+    /// make an eliminated var alive again.
+    fn reactivate(&mut self, vi: VarId);
     /// return the following data:
     /// * the number of vars
     /// * the number of asserted vars
@@ -152,6 +154,13 @@ impl VarManipulateIF for AssignStack {
             panic!("double elimination");
         }
     }
+    fn reactivate(&mut self, vi: VarId) {
+        if self.var[vi].is(Flag::ELIMINATED) {
+            self.var[vi].turn_off(Flag::ELIMINATED);
+            self.insert_heap(vi);
+            self.num_eliminated_vars -= 1;
+        }
+    }
     #[inline]
     fn var_stats(&self) -> (usize, usize, usize, usize, usize) {
         debug_assert!(
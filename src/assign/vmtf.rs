@@ -0,0 +1,159 @@
+/// Var queue for VMTF (Variable Move To Front) decision heuristic
+use {
+    super::{AssignStack, VarIdQueue},
+    crate::types::*,
+};
+
+/// Internal queue manipulation API
+pub trait VarQueueIF {
+    /// bump the vars analyzed since the last call in the order of their last bumps.
+    fn bump_analyzed_vars(&mut self);
+    /// update the search pointer by an unassigned var.
+    fn update_queue(&mut self, vi: VarId);
+    /// return the unassigned var bumped most recently.
+    fn select_queue_var(&mut self) -> VarId;
+    /// restart the search from the most recently bumped var.
+    fn reset_queue(&mut self);
+}
+
+impl VarQueueIF for AssignStack {
+    fn bump_analyzed_vars(&mut self) {
+        let mut analyzed = std::mem::take(&mut self.var_queue.analyzed);
+        let stamp = &self.var_queue.stamp;
+        analyzed.sort_unstable_by_key(|vi| stamp[*vi]);
+        analyzed.dedup();
+        for vi in analyzed.iter() {
+            self.var_queue.move_to_last(*vi);
+            if self.assign[*vi].is_none() {
+                self.var_queue.search = *vi;
+            }
+        }
+        analyzed.clear();
+        self.var_queue.analyzed = analyzed;
+    }
+    fn update_queue(&mut self, vi: VarId) {
+        let q = &mut self.var_queue;
+        if q.stamp[q.search] < q.stamp[vi] {
+            q.search = vi;
+        }
+    }
+    fn select_queue_var(&mut self) -> VarId {
+        let mut vi = self.var_queue.search;
+        while vi != 0 && (self.assign[vi].is_some() || self.var[vi].is(Flag::ELIMINATED)) {
+            vi = self.var_queue.prev[vi];
+        }
+        debug_assert!(vi != 0, "no unassigned var in queue");
+        self.var_queue.search = vi;
+        vi
+    }
+    fn reset_queue(&mut self) {
+        self.var_queue.search = self.var_queue.last;
+    }
+}
+
+impl VarIdQueue {
+    /// return a queue of `n` vars, where var `n` is the most recently bumped.
+    pub fn new(n: usize) -> Self {
+        let mut q = VarIdQueue {
+            prev: vec![0; n + 1],
+            next: vec![0; n + 1],
+            stamp: vec![0; n + 1],
+            ..VarIdQueue::default()
+        };
+        for vi in 1..=n {
+            q.enqueue(vi);
+        }
+        q.search = q.last;
+        q
+    }
+    /// add a new var as the most recently bumped one.
+    pub fn push(&mut self, vi: VarId) {
+        debug_assert_eq!(vi, self.prev.len());
+        self.prev.push(0);
+        self.next.push(0);
+        self.stamp.push(0);
+        self.enqueue(vi);
+        self.search = vi;
+    }
+    /// link a var at the end.
+    fn enqueue(&mut self, vi: VarId) {
+        self.counter += 1;
+        self.stamp[vi] = self.counter;
+        self.prev[vi] = self.last;
+        self.next[vi] = 0;
+        if self.last == 0 {
+            self.first = vi;
+        } else {
+            self.next[self.last] = vi;
+        }
+        self.last = vi;
+    }
+    /// unlink a var.
+    fn dequeue(&mut self, vi: VarId) {
+        let (p, n) = (self.prev[vi], self.next[vi]);
+        if p == 0 {
+            self.first = n;
+        } else {
+            self.next[p] = n;
+        }
+        if n == 0 {
+            self.last = p;
+        } else {
+            self.prev[n] = p;
+        }
+    }
+    /// move a var to the end as the most recently bumped one.
+    fn move_to_last(&mut self, vi: VarId) {
+        if self.search == vi {
+            // Keep the search pointer on a linked var.
+            self.search = match (self.prev[vi], self.next[vi]) {
+                (0, 0) => vi,
+                (0, n) => n,
+                (p, _) => p,
+            };
+        }
+        self.dequeue(vi);
+        self.enqueue(vi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_var_id_queue() {
+        let mut q = VarIdQueue::new(4);
+        assert_eq!((q.first, q.last, q.search), (1, 4, 4));
+        q.move_to_last(2);
+        q.move_to_last(4);
+        q.push(5);
+        let mut order = Vec::new();
+        let mut vi = q.last;
+        while vi != 0 {
+            order.push(vi);
+            vi = q.prev[vi];
+        }
+        assert_eq!(order, vec![5, 4, 2, 3, 1]);
+        assert!(order.windows(2).all(|w| q.stamp[w[1]] < q.stamp[w[0]]));
+        assert_eq!(q.search, 5);
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_vmtf_modes() {
+        use crate::solver::{Certificate, SolveIF, Solver};
+        use std::convert::TryFrom;
+        for in_stable_mode in &[false, true] {
+            for (cnf, sat) in &[("tests/uf100-010.cnf", true), ("tests/unsat.cnf", false)] {
+                let mut s = Solver::try_from(*cnf).expect("can't load");
+                s.asg.use_vmtf = true;
+                s.asg.use_vmtf_in_stable_mode = *in_stable_mode;
+                match s.solve() {
+                    Ok(Certificate::SAT(_)) => assert!(*sat),
+                    Ok(Certificate::UNSAT) => assert!(!*sat),
+                    _ => panic!("failed to solve {}", cnf),
+                }
+            }
+        }
+    }
+}
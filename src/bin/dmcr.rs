@@ -1,8 +1,9 @@
 // DIMACS Model Checker in Rust
 #![allow(unused_imports)]
 use {
-    splr::{Config, SatSolverIF, Solver, ValidateIF},
+    splr::{solver::ProofChecker, Config, SatSolverIF, Solver, ValidateIF},
     std::{
+        convert::TryFrom,
         env,
         fs::File,
         io::{stdin, BufRead, BufReader, Result},
@@ -19,6 +20,8 @@ const RESET: &str = "\x1B[000m";
 struct TargetOpts {
     /// an assign file generated by slpr
     assign: Option<std::path::PathBuf>,
+    /// a DRAT proof file generated by splr
+    proof: Option<std::path::PathBuf>,
     /// a CNF file
     problem: std::path::PathBuf,
     /// disable colorized output
@@ -29,6 +32,7 @@ impl Default for TargetOpts {
     fn default() -> Self {
         TargetOpts {
             assign: None,
+            proof: None,
             problem: PathBuf::new(),
             no_color: false,
         }
@@ -49,7 +53,7 @@ impl TargetOpts {
         while let Some(arg) = iter.next() {
             if let Some(name) = arg.strip_prefix("--") {
                 let flags = ["no-color", "help", "version"];
-                let options_path = ["assign"];
+                let options_path = ["assign", "proof"];
                 if flags.contains(&name) {
                     match name {
                         "no-color" => self.no_color = true,
@@ -62,6 +66,7 @@ impl TargetOpts {
                         if let Some(val) = iter.next() {
                             match name {
                                 "assign" => self.assign = Some(PathBuf::from(val)),
+                                "proof" => self.proof = Some(PathBuf::from(val)),
                                 _ => panic!("not"),
                             }
                         } else {
@@ -73,7 +78,7 @@ impl TargetOpts {
                 }
             } else if let Some(name) = arg.strip_prefix('-') {
                 let flags = ["C", "h", "V"];
-                let options = ["a"];
+                let options = ["a", "p"];
                 if flags.contains(&name) {
                     match name {
                         "C" => self.no_color = true,
@@ -85,6 +90,7 @@ impl TargetOpts {
                     if let Some(val) = iter.next() {
                         match name {
                             "a" => self.assign = Some(PathBuf::from(val)),
+                            "p" => self.proof = Some(PathBuf::from(val)),
                             _ => panic!("invalid option: {}", name),
                         }
                     } else {
@@ -115,6 +121,7 @@ FLAGS:
     -V, --version     Prints version information
 OPTIONS:
     -a, --assign <assign>    an assign file generated by slpr
+    -p, --proof <proof>      a DRAT proof file generated by splr
 ARGS:
     <problem>    a CNF file
 ";
@@ -137,6 +144,29 @@ fn main() {
     } else {
         (RED, GREEN, BLUE)
     };
+    if let Some(ref proof) = args.proof {
+        let result = ProofChecker::try_from(&args.problem).and_then(|mut checker| {
+            checker.inject_proof(proof)?;
+            checker.check()
+        });
+        match result {
+            Ok(_) => println!(
+                "{}A valid proof for {}{} is found in {}",
+                green,
+                args.problem.to_str().unwrap(),
+                RESET,
+                proof.to_str().unwrap(),
+            ),
+            Err(e) => println!(
+                "{}An invalid proof for {}{} due to {}.",
+                red,
+                args.problem.to_str().unwrap(),
+                RESET,
+                e,
+            ),
+        }
+        return;
+    }
     let mut s = Solver::build(&config).expect("failed to load");
     if args.assign == None {
         args.assign = Some(PathBuf::from(format!(
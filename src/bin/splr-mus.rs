@@ -0,0 +1,155 @@
+// Minimal Unsatisfiable Subset extractor in Rust
+use {
+    splr::{solver::MusExtractor, VERSION},
+    std::{
+        convert::TryFrom,
+        fs::File,
+        io::{stdout, BufWriter, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+const ABOUT: &str = "Minimal Unsatisfiable Subset extractor in Rust";
+
+struct TargetOpts {
+    /// an output file
+    output: Option<std::path::PathBuf>,
+    /// a CNF or GCNF file
+    problem: std::path::PathBuf,
+}
+
+impl Default for TargetOpts {
+    fn default() -> Self {
+        TargetOpts {
+            output: None,
+            problem: PathBuf::new(),
+        }
+    }
+}
+
+impl TargetOpts {
+    pub fn inject_from_args(&mut self) {
+        let mut help = false;
+        let mut version = false;
+        if let Some(ref cnf) = std::env::args().next_back() {
+            let path = PathBuf::from(cnf.clone());
+            if path.exists() {
+                self.problem = path;
+            }
+        }
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-h" | "--help" => help = true,
+                "-V" | "--version" => version = true,
+                "-o" | "--output" => {
+                    if let Some(val) = iter.next() {
+                        self.output = Some(PathBuf::from(val));
+                    } else {
+                        panic!("no argument for {}", arg);
+                    }
+                }
+                _ if self.problem.exists() && self.problem.to_string_lossy() == arg => (),
+                _ => panic!("invalid argument: {}", arg),
+            }
+        }
+        if help {
+            println!("{}\n{}", ABOUT, HELP_MESSAGE);
+            std::process::exit(0);
+        }
+        if version {
+            println!("{}", VERSION);
+            std::process::exit(0);
+        }
+    }
+}
+
+const HELP_MESSAGE: &str = "
+USAGE:
+    splr-mus [FLAGS] [OPTIONS] <problem>
+FLAGS:
+    -h, --help        Prints help information
+    -V, --version     Prints version information
+OPTIONS:
+    -o, --output <output>    an output file instead of STDOUT
+ARGS:
+    <problem>    a CNF or group-CNF (GCNF) file
+";
+
+fn main() {
+    let mut args = TargetOpts::default();
+    args.inject_from_args();
+    if !args.problem.exists() {
+        println!("{} does not exist.", args.problem.to_string_lossy());
+        return;
+    }
+    let mut mus = MusExtractor::try_from(&args.problem).expect("failed to load");
+    let groups = match mus.extract() {
+        Ok(Some(v)) => v,
+        Ok(None) => {
+            println!("s SATISFIABLE: {}", args.problem.to_string_lossy());
+            return;
+        }
+        Err(e) => {
+            println!("s UNKNOWN ({}): {}", e, args.problem.to_string_lossy());
+            return;
+        }
+    };
+    let mut buf: Box<dyn Write> = match args.output {
+        Some(ref file) => Box::new(BufWriter::new(
+            File::create(file).expect("failed to create the output file"),
+        )),
+        None => Box::new(BufWriter::new(stdout())),
+    };
+    if let Err(why) = save_mus(&mus, &groups, &args.problem, &mut buf) {
+        println!("Abort: failed to save by {}!", why);
+    }
+}
+
+/// write a MUS in CNF, or GCNF if the input has hard clauses or multi-clause groups.
+fn save_mus(
+    mus: &MusExtractor,
+    groups: &[usize],
+    input: &Path,
+    buf: &mut dyn Write,
+) -> std::io::Result<()> {
+    let hard = mus.clauses(0);
+    let gcnf = !hard.is_empty() || groups.iter().any(|g| mus.clauses(*g).len() != 1);
+    let num_clauses = hard.len() + groups.iter().map(|g| mus.clauses(*g).len()).sum::<usize>();
+    buf.write_all(
+        format!(
+            "c A minimal unsatisfiable subset generated by splr-{} for {}\nc #solve: {}, #rotated: {}\nc groups: {:?}\n",
+            VERSION,
+            input.to_string_lossy(),
+            mus.num_solve,
+            mus.num_rotated,
+            groups,
+        )
+        .as_bytes(),
+    )?;
+    if gcnf {
+        buf.write_all(
+            format!(
+                "p gcnf {} {} {}\n",
+                mus.num_vars(),
+                num_clauses,
+                groups.last().unwrap_or(&0)
+            )
+            .as_bytes(),
+        )?;
+    } else {
+        buf.write_all(format!("p cnf {} {}\n", mus.num_vars(), num_clauses).as_bytes())?;
+    }
+    for g in std::iter::once(&0).chain(groups.iter()) {
+        for c in mus.clauses(*g) {
+            if gcnf {
+                buf.write_all(format!("{{{}}} ", g).as_bytes())?;
+            }
+            for l in c.iter() {
+                buf.write_all(format!("{} ", l).as_bytes())?;
+            }
+            buf.write_all(b"0\n")?;
+        }
+    }
+    buf.flush()
+}
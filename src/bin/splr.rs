@@ -1,18 +1,23 @@
 // SAT solver for Propositional Logic in Rust
 use {
     splr::{
-        cdb::CertifiedRecord,
+        assign::VarSelectIF,
         solver::*,
         state::{LogF64Id, LogUsizeId},
-        types::Export,
+        types::{CNFReader, Export},
         Config, SolverError, VERSION,
     },
     std::{
         borrow::Cow,
+        convert::TryFrom,
         env,
         fs::File,
-        io::{BufWriter, Write},
-        path::PathBuf,
+        io::{BufRead, BufWriter, Write},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
         thread,
         time::Duration,
     },
@@ -23,18 +28,18 @@ const GREEN: &str = "\x1B[001m\x1B[032m";
 const BLUE: &str = "\x1B[001m\x1B[034m";
 const RESET: &str = "\x1B[000m";
 
-fn colored(v: Result<bool, &SolverError>, quiet: bool) -> Cow<'static, str> {
+fn colored(v: Option<bool>, quiet: bool) -> Cow<'static, str> {
     if quiet {
         match v {
-            Ok(false) => Cow::Borrowed("s UNSATISFIABLE"),
-            Ok(true) => Cow::Borrowed("s SATISFIABLE"),
-            Err(_) => Cow::Borrowed("s UNKNOWN"),
+            Some(false) => Cow::Borrowed("s UNSATISFIABLE"),
+            Some(true) => Cow::Borrowed("s SATISFIABLE"),
+            None => Cow::Borrowed("s UNKNOWN"),
         }
     } else {
         match v {
-            Ok(false) => Cow::from(format!("{}s UNSATISFIABLE{}", GREEN, RESET)),
-            Ok(true) => Cow::from(format!("{}s SATISFIABLE{}", BLUE, RESET)),
-            Err(_) => Cow::from(format!("{}s UNKNOWN{}", RED, RESET)),
+            Some(false) => Cow::from(format!("{}s UNSATISFIABLE{}", GREEN, RESET)),
+            Some(true) => Cow::from(format!("{}s SATISFIABLE{}", BLUE, RESET)),
+            None => Cow::from(format!("{}s UNKNOWN{}", RED, RESET)),
         }
     }
 }
@@ -63,28 +68,38 @@ fn main() {
         println!("Abort: You set a proof filename with '--proof' explicitly, but didn't set '--certify'. It doesn't look good.");
         return;
     }
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let loaded = Arc::new(AtomicBool::new(false));
     if let Ok(val) = env::var("SPLR_TIMEOUT") {
         if let Ok(timeout) = val.parse::<u64>() {
             let input = cnf_file.as_ref().to_string();
             let quiet_mode = config.quiet_mode;
+            let handle = Arc::clone(&interrupt);
+            let loaded = Arc::clone(&loaded);
             thread::spawn(move || {
                 thread::sleep(Duration::from_millis(timeout * 1000));
-                println!(
-                    "{} (TimeOut): {}",
-                    colored(Err(&SolverError::TimeOut), quiet_mode),
-                    input
-                );
-                std::process::exit(0);
+                handle.store(true, Ordering::Relaxed);
+                // The solver can't be stopped while loading the CNF.
+                if !loaded.load(Ordering::Relaxed) {
+                    println!("{} (TimeOut): {}", colored(None, quiet_mode), input);
+                    std::process::exit(0);
+                }
             });
         }
     }
     let mut s = Solver::build(&config).expect("failed to load");
-    let res = s.solve();
+    s.state.interrupt = Arc::clone(&interrupt);
+    loaded.store(true, Ordering::Relaxed);
+    let res = match s.solve() {
+        // Only the time limits stop the solver.
+        Ok(Certificate::UNKNOWN) => Err(SolverError::TimeOut),
+        res => res,
+    };
     save_result(&s, &res, &cnf_file, ans_file);
     std::process::exit(match res {
         Ok(Certificate::SAT(_)) => 10,
         Ok(Certificate::UNSAT) => 20,
-        Err(_) => 0,
+        Ok(Certificate::UNKNOWN) | Err(_) => 0,
     });
 }
 
@@ -125,7 +140,7 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
             }
             println!(
                 "{}: {}",
-                colored(Ok(true), s.state.config.quiet_mode),
+                colored(Some(true), s.state.config.quiet_mode),
                 input
             );
             if let Err(why) = (|| {
@@ -156,16 +171,22 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
                 _ => (),
             }
             if s.state.config.use_certification {
-                let proof_file: PathBuf = s.state.config.io_odir.join(&s.state.config.io_pfile);
-                save_proof(&s, &input, &proof_file);
                 println!(
                     " Certificate|file: {}",
                     s.state.config.io_pfile.to_string_lossy()
                 );
             }
+            if s.state.config.use_core {
+                let file = s.state.config.io_odir.join(&s.state.config.io_cfile);
+                match save_core(s, &file) {
+                    Ok(true) => println!("  UNSAT core|file: {}", file.to_string_lossy()),
+                    Ok(false) => println!("  UNSAT core|none: the refutation can't be traced."),
+                    Err(why) => println!("Abort: failed to save the UNSAT core by {}!", why),
+                }
+            }
             println!(
                 "{}: {}",
-                colored(Ok(false), s.state.config.quiet_mode),
+                colored(Some(false), s.state.config.quiet_mode),
                 input
             );
             if let Err(why) = (|| {
@@ -183,7 +204,12 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
                 println!("Abort: failed to save by {}!", why);
             }
         }
-        Err(e) => {
+        Ok(Certificate::UNKNOWN) | Err(_) => {
+            // A budget or an interrupt stopped the search without an answer.
+            let why = match res {
+                Err(e) => format!("{}", e),
+                _ => "Interrupted".to_string(),
+            };
             match output {
                 Some(ref f) if redirect => println!(
                     "      Result|dump: to STDOUT instead of {} due to an IO error.",
@@ -194,8 +220,8 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
             }
             println!(
                 "{} ({}): {}",
-                colored(Err(e), s.state.config.quiet_mode),
-                e,
+                colored(None, s.state.config.quiet_mode),
+                why,
                 input
             );
             if let Err(why) = (|| {
@@ -207,7 +233,7 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
                     .as_bytes(),
                 )?;
                 report(s, buf)?;
-                buf.write_all(format!("c {}\n{}\n", e, colored(Err(e), true)).as_bytes())?;
+                buf.write_all(format!("c {}\n{}\n", why, colored(None, true)).as_bytes())?;
                 buf.write(b"0\n")
             })() {
                 println!("Abort: failed to save by {}!", why);
@@ -216,41 +242,55 @@ fn save_result<S: AsRef<str> + std::fmt::Display>(
     }
 }
 
-fn save_proof<S: AsRef<str> + std::fmt::Display>(s: &Solver, input: S, output: &PathBuf) {
-    let mut buf = match File::create(output) {
-        Ok(out) => BufWriter::new(out),
-        Err(e) => {
-            println!(
-                "Abort: failed to create the proof file {:?} by {}!",
-                output.to_string_lossy(),
-                e
-            );
-            return;
-        }
+/// write the given clauses in the UNSAT core; return `false` if no core was found.
+fn save_core(s: &Solver, output: &Path) -> std::io::Result<bool> {
+    let core = match s.unsat_core() {
+        Some(v) => v,
+        None => return Ok(false),
     };
-    if let Err(why) = (|| {
-        buf.write_all(
-            format!("c Proof generated by splr-{} for {}\nc \n", VERSION, input).as_bytes(),
-        )?;
-        buf.write_all(b"s UNSATISFIABLE\n")?;
-        for (f, x) in &s.cdb.certified[1..] {
-            if *f == CertifiedRecord::DELETE {
-                buf.write_all(b"d ")?;
+    let CNFReader { reader, .. } = CNFReader::try_from(&s.state.config.cnf_file)
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+    let mut buf = BufWriter::new(File::create(output)?);
+    buf.write_all(
+        format!(
+            "c An UNSAT core generated by splr-{} for {}\nc \np cnf {} {}\n",
+            VERSION,
+            s.state.target.pathname,
+            s.state.target.num_of_variables,
+            core.len(),
+        )
+        .as_bytes(),
+    )?;
+    // Clauses are counted in the same way as `Solver::build`.
+    let mut index = 0;
+    let mut iter = core.iter().peekable();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('c') {
+            continue;
+        }
+        let mut v: Vec<i32> = Vec::new();
+        for s in line.split_whitespace() {
+            match s.parse::<i32>() {
+                Ok(0) => break,
+                Ok(val) => v.push(val),
+                Err(_) => (),
             }
-            for l in x {
+        }
+        if v.is_empty() {
+            continue;
+        }
+        if iter.peek() == Some(&&index) {
+            iter.next();
+            for l in v.iter() {
                 buf.write_all(format!("{} ", l).as_bytes())?;
             }
             buf.write_all(b"0\n")?;
         }
-        buf.write_all(b"0\n")
-    })() {
-        println!(
-            "Abort: failed to save to {} by {}!",
-            output.to_string_lossy(),
-            why
-        );
-        return;
+        index += 1;
     }
+    buf.flush()?;
+    Ok(true)
 }
 
 fn report(s: &Solver, out: &mut dyn Write) -> std::io::Result<()> {
@@ -296,10 +336,10 @@ fn report(s: &Solver, out: &mut dyn Write) -> std::io::Result<()> {
     out.write_all(
         format!(
             "c  {}|#BLK:{}, #RST:{}, #ion:{}, Lcyc:{},\n",
-            if s.rst.mode().0 == RestartMode::Luby {
-                "LubyRestart"
-            } else {
-                "    Restart"
+            match s.rst.mode().0 {
+                RestartMode::Luby => "LubyRestart",
+                RestartMode::Geometric => " GeoRestart",
+                _ => "    Restart",
             },
             format!("{:>9}", state[LogUsizeId::RestartBlock]),
             format!("{:>9}", state[LogUsizeId::Restart]),
@@ -308,6 +348,16 @@ fn report(s: &Solver, out: &mut dyn Write) -> std::io::Result<()> {
         )
         .as_bytes(),
     )?;
+    out.write_all(
+        format!(
+            "c        Phase|mode:{:>9}, #rph:{}, tgt#:{}, bst#:{},\n",
+            s.asg.phase_stats().0,
+            format!("{:>9}", state[LogUsizeId::Rephase]),
+            format!("{:>9}", state[LogUsizeId::TargetAssign]),
+            format!("{:>9}", state[LogUsizeId::BestAssign]),
+        )
+        .as_bytes(),
+    )?;
     out.write_all(
         format!(
             "c          EMA|tLBD:{}, tASG:{}, core:{}, /dpc:{},\n",
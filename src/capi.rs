@@ -0,0 +1,314 @@
+//! Crate `capi` implements the standard incremental SAT solver interface, IPASIR.
+//! The crate `splr-ipasir` in the workspace builds it as a `cdylib` and a `staticlib`
+//! to link with an IPASIR application.
+use {
+    crate::{
+        config::Config,
+        solver::{Certificate, SatSolverIF, SolveIF, Solver},
+        state::{LearntCallback, TerminateCallback},
+        types::*,
+    },
+    std::os::raw::{c_char, c_int, c_void},
+};
+
+/// An IPASIR solver, which holds the clause and the assumptions under construction.
+struct IpasirSolver {
+    solver: Solver,
+    /// the literals of the clause under construction
+    clause: Vec<i32>,
+    /// the assumptions for the next invocation
+    assumptions: Vec<i32>,
+    /// the model found by the last invocation
+    model: Vec<i32>,
+    /// the failed assumptions found by the last invocation
+    failed: Vec<i32>,
+    /// `true` if the clauses are unsatisfiable without any assumption
+    inconsistent: bool,
+}
+
+impl IpasirSolver {
+    fn new() -> Self {
+        let mut config = Config::default();
+        config.quiet_mode = true;
+        let solver = Solver::instantiate(&config, &CNFDescription::default());
+        IpasirSolver {
+            solver,
+            clause: Vec::new(),
+            assumptions: Vec::new(),
+            model: Vec::new(),
+            failed: Vec::new(),
+            inconsistent: false,
+        }
+    }
+    /// add vars until the solver has the var of `lit`.
+    /// They are frozen because any of them can occur in clauses added later, under which
+    /// elimination isn't sound.
+    fn import(&mut self, lit: i32) {
+        while self.solver.asg.num_vars < lit.unsigned_abs() as usize {
+            let vi = self.solver.add_var();
+            self.solver.freeze(vi).expect("a new var is in range");
+        }
+    }
+    fn add(&mut self, lit: i32) {
+        if lit != 0 {
+            self.import(lit);
+            self.clause.push(lit);
+            return;
+        }
+        let clause = std::mem::take(&mut self.clause);
+        if !self.inconsistent && self.solver.add_clause(clause).is_err() {
+            self.inconsistent = true;
+        }
+    }
+    fn solve(&mut self) -> c_int {
+        let assumptions = std::mem::take(&mut self.assumptions);
+        self.model.clear();
+        self.failed.clear();
+        if self.inconsistent {
+            return 20;
+        }
+        match self.solver.solve_with_assumptions(&assumptions) {
+            Ok(Certificate::SAT(model)) => {
+                self.model = model;
+                10
+            }
+            Ok(Certificate::UNSAT) => {
+                self.failed = self.solver.failed_assumptions();
+                if self.failed.is_empty() {
+                    self.inconsistent = true;
+                }
+                20
+            }
+            _ => 0,
+        }
+    }
+    fn val(&self, lit: i32) -> i32 {
+        if lit == 0 {
+            return 0;
+        }
+        match self.model.get(lit.unsigned_abs() as usize - 1) {
+            Some(v) if 0 < *v => lit,
+            Some(_) => -lit,
+            None => 0,
+        }
+    }
+}
+
+/// A pointer passed back to callbacks.
+#[derive(Clone, Copy)]
+struct Data(*mut c_void);
+
+// The pointer is only passed to the callbacks given with it.
+unsafe impl Send for Data {}
+
+/// return the name and the version of the solver.
+#[no_mangle]
+pub extern "C" fn ipasir_signature() -> *const c_char {
+    concat!("splr-", env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// return a new solver.
+#[no_mangle]
+pub extern "C" fn ipasir_init() -> *mut c_void {
+    Box::into_raw(Box::new(IpasirSolver::new())) as *mut c_void
+}
+
+/// release a solver made by `ipasir_init`.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init` and is invalid after the call.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_release(solver: *mut c_void) {
+    drop(Box::from_raw(solver as *mut IpasirSolver));
+}
+
+/// add a literal to the clause under construction; zero terminates the clause.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_add(solver: *mut c_void, lit_or_zero: i32) {
+    (*(solver as *mut IpasirSolver)).add(lit_or_zero);
+}
+
+/// add an assumption for the next invocation of `ipasir_solve`.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_assume(solver: *mut c_void, lit: i32) {
+    let s = &mut *(solver as *mut IpasirSolver);
+    s.import(lit);
+    s.assumptions.push(lit);
+}
+
+/// solve the problem under the assumptions and clear them.
+/// Return 10 for SAT, 20 for UNSAT, and 0 if it's interrupted.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_solve(solver: *mut c_void) -> c_int {
+    (*(solver as *mut IpasirSolver)).solve()
+}
+
+/// return `lit` if it's true, `-lit` if it's false in the last model, or 0 for an unknown var.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_val(solver: *mut c_void, lit: i32) -> i32 {
+    (*(solver as *mut IpasirSolver)).val(lit)
+}
+
+/// return 1 if the assumption `lit` was used to refute the last invocation, otherwise 0.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_failed(solver: *mut c_void, lit: i32) -> c_int {
+    (*(solver as *mut IpasirSolver)).failed.contains(&lit) as c_int
+}
+
+/// set a callback polled during the search, which returns non-zero to stop it.
+/// A null `terminate` removes the callback.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+/// `data` must be valid as long as the callback is set.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_terminate(
+    solver: *mut c_void,
+    data: *mut c_void,
+    terminate: Option<extern "C" fn(data: *mut c_void) -> c_int>,
+) {
+    let data = Data(data);
+    (*(solver as *mut IpasirSolver))
+        .solver
+        .set_terminate(terminate.map(|f| {
+            Box::new(move || {
+                let data = data;
+                f(data.0) != 0
+            }) as TerminateCallback
+        }));
+}
+
+/// set a callback which receives every learnt clause up to `max_length` literals
+/// as a zero-terminated array. A null `learn` removes the callback.
+///
+/// # Safety
+///
+/// `solver` must be a pointer returned by `ipasir_init`.
+/// `data` must be valid as long as the callback is set.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_learn(
+    solver: *mut c_void,
+    data: *mut c_void,
+    max_length: c_int,
+    learn: Option<extern "C" fn(data: *mut c_void, clause: *mut i32)>,
+) {
+    let data = Data(data);
+    (*(solver as *mut IpasirSolver)).solver.set_learnt_callback(
+        max_length.max(0) as usize,
+        0,
+        learn.map(|f| {
+            Box::new(move |lits: &[i32]| {
+                let data = data;
+                let mut clause = lits.to_vec();
+                clause.push(0);
+                f(data.0, clause.as_mut_ptr());
+            }) as LearntCallback
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::ptr};
+
+    fn add_clause(s: *mut c_void, clause: &[i32]) {
+        for l in clause.iter().chain(std::iter::once(&0)) {
+            unsafe { ipasir_add(s, *l) };
+        }
+    }
+
+    extern "C" fn count_learnt(data: *mut c_void, clause: *mut i32) {
+        unsafe {
+            let mut len = 0;
+            while *clause.add(len) != 0 {
+                len += 1;
+            }
+            assert!(len <= 3);
+            *(data as *mut usize) += 1;
+        }
+    }
+
+    extern "C" fn stop(_: *mut c_void) -> c_int {
+        1
+    }
+
+    #[test]
+    fn test_ipasir() {
+        let s = ipasir_init();
+        unsafe {
+            add_clause(s, &[1, 2]);
+            add_clause(s, &[-1, 3]);
+            add_clause(s, &[-2, 3]);
+            assert_eq!(ipasir_solve(s), 10);
+            assert_eq!(ipasir_val(s, 3), 3);
+            assert_eq!(ipasir_val(s, -3), -3);
+            assert_eq!(ipasir_val(s, 4), 0);
+            assert_eq!(ipasir_val(s, 0), 0);
+            ipasir_assume(s, -3);
+            ipasir_assume(s, 4);
+            assert_eq!(ipasir_solve(s), 20);
+            assert_eq!(ipasir_failed(s, -3), 1);
+            assert_eq!(ipasir_failed(s, 4), 0);
+            // Assumptions are cleared after each invocation.
+            assert_eq!(ipasir_solve(s), 10);
+            add_clause(s, &[-3]);
+            assert_eq!(ipasir_solve(s), 20);
+            assert_eq!(ipasir_solve(s), 20);
+            ipasir_release(s);
+        }
+    }
+
+    #[test]
+    fn test_ipasir_callbacks() {
+        let s = ipasir_init();
+        let mut num_learnts: usize = 0;
+        unsafe {
+            // pigeonhole: 6 pigeons into 5 holes
+            let var = |p: i32, h: i32| p * 5 + h + 1;
+            for p in 0..6 {
+                add_clause(s, &(0..5).map(|h| var(p, h)).collect::<Vec<_>>());
+            }
+            for h in 0..5 {
+                for p in 0..6 {
+                    for q in p + 1..6 {
+                        add_clause(s, &[-var(p, h), -var(q, h)]);
+                    }
+                }
+            }
+            ipasir_set_terminate(s, ptr::null_mut(), Some(stop));
+            assert_eq!(ipasir_solve(s), 0);
+            ipasir_set_terminate(s, ptr::null_mut(), None);
+            ipasir_set_learn(
+                s,
+                &mut num_learnts as *mut usize as *mut c_void,
+                3,
+                Some(count_learnt),
+            );
+            assert_eq!(ipasir_solve(s), 20);
+            ipasir_release(s);
+        }
+        assert!(0 < num_learnts);
+    }
+}
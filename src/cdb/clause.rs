@@ -30,6 +30,7 @@ impl Default for Clause {
             search_from: 2,
             reward: 0.0,
             flags: Flag::empty(),
+            proof_id: 0,
         }
     }
 }
@@ -180,7 +181,7 @@ impl Ord for Clause {
     fn cmp(&self, other: &Clause) -> Ordering {
         if self.rank < other.rank {
             Ordering::Less
-        } else if other.rank > self.rank {
+        } else if other.rank < self.rank {
             Ordering::Greater
         } else if self.reward > other.reward {
             Ordering::Less
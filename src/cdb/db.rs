@@ -1,7 +1,7 @@
 #[cfg(feature = "strategy_adaptation")]
 use crate::state::SearchStrategy;
 use {
-    super::{CertifiedRecord, Clause, ClauseDB, ClauseId, WatchDBIF},
+    super::{Clause, ClauseDB, ClauseId, ProofSink, WatchDBIF},
     crate::{assign::AssignIF, solver::SolverEvent, types::*},
     std::{
         cmp::Ordering,
@@ -50,13 +50,22 @@ pub trait ClauseDBIF: IndexMut<ClauseId, Output = Clause> {
     /// allocate a new clause and return its id.
     /// * If `level_sort` is on, register `v` as a learnt after sorting based on assign level.
     /// * Otherwise, register `v` as a permanent clause, which rank is zero.
+    /// * `hints` are the proof ids of the antecedents used in LRAT certification.
     fn new_clause<A>(
         &mut self,
         asg: &mut A,
         v: &mut Vec<Lit>,
         learnt: bool,
         level_sort: bool,
+        hints: &[usize],
     ) -> ClauseId
+    where
+        A: AssignIF;
+    /// return the proof id of the next clause given from the problem.
+    fn given_proof_id(&mut self) -> usize;
+    /// allocate a clause given from the problem and return its id.
+    /// It isn't recorded to certification because it's a part of the input.
+    fn new_given_clause<A>(&mut self, asg: &mut A, v: &mut Vec<Lit>, proof_id: usize) -> ClauseId
     where
         A: AssignIF;
     /// update LBD then convert a learnt clause to permanent if needed.
@@ -67,10 +76,28 @@ pub trait ClauseDBIF: IndexMut<ClauseId, Output = Clause> {
     fn count(&self) -> usize;
     /// return the number of clauses which satisfy given flags and aren't DEAD.
     fn countf(&self, mask: Flag) -> usize;
-    /// record a clause to unsat certification.
-    fn certificate_add(&mut self, vec: &[Lit]);
+    /// return `true` if certification requires the antecedents of added clauses.
+    fn needs_hints(&self) -> bool;
+    /// record a clause derived from `hints` to unsat certification.
+    fn certificate_add(&mut self, vec: &[Lit], hints: &[usize]);
     /// record a deleted clause to unsat certification.
-    fn certificate_delete(&mut self, vec: &[Lit]);
+    fn certificate_delete(&mut self, proof_id: usize, vec: &[Lit]);
+    /// record a literal implied by clause `cid` at decision level zero as a unit clause.
+    fn certificate_implied_unit(&mut self, l: Lit, cid: ClauseId);
+    /// record the empty clause derived from the assignments at decision level zero.
+    fn certificate_refutation<A>(&mut self, asg: &A)
+    where
+        A: AssignIF;
+    /// return the proof id of a unit clause `l`. Zero means it's unknown.
+    fn unit_proof_id(&self, l: Lit) -> usize;
+    /// set the proof id of a unit clause `l`.
+    fn set_unit_proof_id(&mut self, l: Lit, proof_id: usize);
+    /// return the proof ids to derive `vec` by unit propagation from the conflict by `cid`.
+    /// All literals in `vec` must be falsified under the present assignment.
+    /// Return `None` if it depends on a decision or a clause out of certification.
+    fn derive_hints<A>(&mut self, asg: &A, vec: &[Lit], cid: ClauseId) -> Option<Vec<usize>>
+    where
+        A: AssignIF;
     /// flag positive and negative literals of a var as dirty
     fn touch_var(&mut self, vi: VarId);
     /// check the number of clauses
@@ -84,16 +111,14 @@ pub trait ClauseDBIF: IndexMut<ClauseId, Output = Clause> {
     fn validate(&self, model: &[Option<bool>], strict: bool) -> Option<ClauseId>;
     /// removes Lit `p` from Clause *self*. This is an O(n) function!
     /// This returns `true` if the clause became a unit clause.
+    /// Otherwise the new clause is recorded as an addition and the old one as a deletion.
+    /// `hint` is the proof id of a clause which derives `!p` with the others.
     /// And this is called only from `Eliminator::strengthen_clause`.
-    fn strengthen(&mut self, cid: ClauseId, p: Lit) -> bool;
+    fn strengthen(&mut self, cid: ClauseId, p: Lit, hint: usize) -> bool;
     /// minimize a clause.
     fn minimize_with_biclauses<A>(&mut self, asg: &A, vec: &mut Vec<Lit>)
     where
         A: AssignIF;
-
-    #[cfg(feature = "incremental_solver")]
-    /// save an eliminated permanent clause to an extra space for incremental solving.
-    fn make_permanent_immortal(&mut self, cid: ClauseId);
 }
 
 impl Default for ClauseDB {
@@ -102,7 +127,11 @@ impl Default for ClauseDB {
             clause: Vec::new(),
             bin_watcher: Vec::new(),
             watcher: Vec::new(),
-            certified: Vec::new(),
+            certified: ProofSink::default(),
+            num_given: 0,
+            next_proof_id: 1,
+            unit_proof_id: Vec::new(),
+            hint_temp: Vec::new(),
             soft_limit: 0, // 248_000_000
             use_chan_seok: false,
             co_lbd_bound: 5,
@@ -125,7 +154,6 @@ impl Default for ClauseDB {
             num_learnt: 0,
             num_reduction: 0,
             during_vivification: false,
-            eliminated_permanent: Vec::new(),
         }
     }
 }
@@ -232,17 +260,15 @@ impl Instantiate for ClauseDB {
             watcher.push(Vec::new());
             touched.push(false);
         }
-        let mut certified = Vec::new();
-        if config.use_certification {
-            certified.push((CertifiedRecord::SENTINEL, Vec::new()));
-        }
         ClauseDB {
             clause,
             touched,
+            next_proof_id: nc + 1,
+            unit_proof_id: vec![0; 2 * (nv + 1)],
+            hint_temp: vec![0; nv + 1],
             lbd_temp: vec![0; nv + 1],
             bin_watcher,
             watcher,
-            certified,
             reducible: config.use_reduce(),
             soft_limit: config.c_cls_lim,
             ..ClauseDB::default()
@@ -291,6 +317,9 @@ impl Instantiate for ClauseDB {
                 self.touched.push(false);
                 // for positive literal
                 self.touched.push(false);
+                self.unit_proof_id.push(0);
+                self.unit_proof_id.push(0);
+                self.hint_temp.push(0);
                 self.lbd_temp.push(0);
             }
             SolverEvent::Vivify(on) => {
@@ -395,10 +424,8 @@ impl ClauseDBIF for ClauseDB {
                     if c.is(Flag::LEARNT) {
                         self.num_learnt -= 1;
                     }
-                    if !certified.is_empty() && !c.is(Flag::VIV_ASSUMED) {
-                        let temp = c.lits.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
-                        debug_assert!(!temp.is_empty());
-                        certified.push((CertifiedRecord::DELETE, temp));
+                    if !c.is(Flag::VIV_ASSUMED) {
+                        certified.delete(c.proof_id, &c.lits);
                     }
                     c.lits.clear();
                 }
@@ -432,10 +459,8 @@ impl ClauseDBIF for ClauseDB {
                     if c.is(Flag::LEARNT) {
                         self.num_learnt -= 1;
                     }
-                    if !certified.is_empty() && !c.is(Flag::VIV_ASSUMED) {
-                        let temp = c.lits.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
-                        debug_assert!(!temp.is_empty());
-                        certified.push((CertifiedRecord::DELETE, temp));
+                    if !c.is(Flag::VIV_ASSUMED) {
+                        certified.delete(c.proof_id, &c.lits);
                     }
                     c.lits.clear();
                 }
@@ -447,7 +472,8 @@ impl ClauseDBIF for ClauseDB {
     }
     fn registered_bin_clause(&self, l0: Lit, l1: Lit) -> bool {
         for w in &self.bin_watcher_lists()[usize::from(!l0)] {
-            if w.blocker == l1 {
+            // A detached clause is watched until the next garbage collection.
+            if w.blocker == l1 && !self[w.c].is(Flag::DEAD) {
                 return true;
             }
         }
@@ -457,113 +483,32 @@ impl ClauseDBIF for ClauseDB {
         &mut self,
         asg: &mut A,
         vec: &mut Vec<Lit>,
-        mut learnt: bool,
+        learnt: bool,
         level_sort: bool,
+        hints: &[usize],
     ) -> ClauseId
     where
         A: AssignIF,
     {
-        let reward = self.activity_inc;
-        if level_sort {
-            #[cfg(feature = "boundary_check")]
-            debug_assert!(1 < vec.len());
-            // // sort literals
-            // let mut i_max = 1;
-            // let mut lv_max = 0;
-            // // seek a literal with max level
-            // let level = asg.level_ref();
-            // for (i, l) in vec.iter().enumerate() {
-            //     let vi = l.vi();
-            //     let lv = level[vi];
-            //     if asg.assign(vi).is_some() && lv_max < lv {
-            //         i_max = i;
-            //         lv_max = lv;
-            //     }
-            // }
-            // vec.swap(1, i_max);
-        }
-        if !self.certified.is_empty() && !self.during_vivification {
-            let temp = vec.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
-            debug_assert!(!temp.is_empty());
-            self.certified.push((CertifiedRecord::ADD, temp));
-        }
-        let cid;
-        let l0 = vec[0];
-        let l1 = vec[1];
-        if let Some(w) = self.watcher[!NULL_LIT].pop() {
-            cid = w.c;
-            let c = &mut self[cid];
-            // if !c.is(Flag::DEAD) {
-            //     println!("{} {:?}", cid.format(), vec2int(&c.lits));
-            //     println!("len {}", self.watcher[NULL_LIT.negate() as usize].len());
-            //     for w in &self.watcher[NULL_LIT.negate() as usize][..10] {
-            //         if !self.clause[w.c].is(Flag::DEAD) {
-            //             println!("{}", w.c.format());
-            //         }
-            //     }
-            //     panic!("done");
-            // }
-            debug_assert!(c.is(Flag::DEAD));
-            c.flags = Flag::empty();
-            debug_assert!(c.lits.is_empty()); // c.lits.clear();
-            std::mem::swap(&mut c.lits, vec);
-            c.reward = reward;
-            c.search_from = 2;
-        } else {
-            cid = ClauseId::from(self.clause.len());
-            let mut c = Clause {
-                flags: Flag::empty(),
-                reward,
-                ..Clause::default()
-            };
-            std::mem::swap(&mut c.lits, vec);
-            self.clause.push(c);
-        };
-        if self.during_vivification {
-            self[cid].turn_on(Flag::VIV_ASSUMED);
+        let mut proof_id = 0;
+        if !self.during_vivification {
+            proof_id = self.next_proof_id;
+            self.next_proof_id += 1;
+            self.certified.add(proof_id, vec, hints);
         }
-        {
-            let ClauseDB {
-                ref mut clause,
-                ref mut lbd_temp,
-                ref mut num_active,
-                ref mut num_bi_clause,
-                ref mut num_bi_learnt,
-                ref mut num_lbd2,
-                ref mut num_learnt,
-                ref mut bin_watcher,
-                ref mut watcher,
-                ..
-            } = self;
-            let c = &mut clause[cid.ordinal as usize];
-            c.update_lbd(asg, lbd_temp);
-            let len2 = c.lits.len() == 2;
-            if c.lits.len() <= 2 || (self.use_chan_seok && c.rank as usize <= self.co_lbd_bound) {
-                learnt = false;
-            }
-            if learnt {
-                c.turn_on(Flag::LEARNT);
-                c.turn_on(Flag::JUST_USED);
-
-                if len2 {
-                    *num_bi_learnt += 1;
-                }
-                if c.rank <= 2 {
-                    *num_lbd2 += 1;
-                }
-                *num_learnt += 1;
-            }
-            if len2 {
-                *num_bi_clause += 1;
-                bin_watcher[!l0].register(l1, cid);
-                bin_watcher[!l1].register(l0, cid);
-            } else {
-                watcher[!l0].register(l1, cid);
-                watcher[!l1].register(l0, cid);
-            }
-            *num_active += 1;
-        }
-        cid
+        self.register_clause(asg, vec, learnt, level_sort, proof_id)
+    }
+    fn given_proof_id(&mut self) -> usize {
+        self.num_given += 1;
+        // More clauses than the CNF header declared can be given in incremental solving.
+        self.next_proof_id = self.next_proof_id.max(self.num_given + 1);
+        self.num_given
+    }
+    fn new_given_clause<A>(&mut self, asg: &mut A, vec: &mut Vec<Lit>, proof_id: usize) -> ClauseId
+    where
+        A: AssignIF,
+    {
+        self.register_clause(asg, vec, false, false, proof_id)
     }
     fn mark_clause_as_used<A>(&mut self, asg: &mut A, cid: ClauseId) -> bool
     where
@@ -657,18 +602,95 @@ impl ClauseDBIF for ClauseDB {
         }
         self.garbage_collect();
     }
-    fn certificate_add(&mut self, vec: &[Lit]) {
-        if !self.certified.is_empty() && !self.during_vivification {
-            let temp = vec.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
-            debug_assert!(!temp.is_empty());
-            self.certified.push((CertifiedRecord::ADD, temp));
+    fn needs_hints(&self) -> bool {
+        self.certified.needs_hints()
+    }
+    fn certificate_add(&mut self, vec: &[Lit], hints: &[usize]) {
+        if !self.during_vivification {
+            let proof_id = self.next_proof_id;
+            self.next_proof_id += 1;
+            self.certified.add(proof_id, vec, hints);
+            if vec.len() == 1 {
+                self.unit_proof_id[usize::from(vec[0])] = proof_id;
+            }
+        }
+    }
+    fn certificate_delete(&mut self, proof_id: usize, vec: &[Lit]) {
+        if !self.during_vivification {
+            self.certified.delete(proof_id, vec);
+        }
+    }
+    fn certificate_implied_unit(&mut self, l: Lit, cid: ClauseId) {
+        if !self.needs_hints() || self.unit_proof_id[usize::from(l)] != 0 {
+            return;
+        }
+        let c = &self.clause[cid.ordinal as usize];
+        if c.proof_id == 0 {
+            return;
+        }
+        let mut hints = Vec::with_capacity(c.lits.len());
+        for q in c.lits.iter().filter(|q| **q != l) {
+            match self.unit_proof_id[usize::from(!*q)] {
+                0 => return,
+                id => hints.push(id),
+            }
+        }
+        hints.push(c.proof_id);
+        self.certificate_add(&[l], &hints);
+    }
+    fn certificate_refutation<A>(&mut self, asg: &A)
+    where
+        A: AssignIF,
+    {
+        if !self.needs_hints() {
+            self.certificate_add(&[], &[]);
+            return;
+        }
+        // a pair of complementary unit clauses
+        for l in asg.stack_iter() {
+            let (pos, neg) = (self.unit_proof_id(*l), self.unit_proof_id(!*l));
+            if pos != 0 && neg != 0 {
+                self.certificate_add(&[], &[pos, neg]);
+                return;
+            }
         }
+        // a clause falsified by unit clauses
+        'next: for c in self.clause.iter().skip(1) {
+            if c.is(Flag::DEAD) || c.proof_id == 0 {
+                continue;
+            }
+            let mut hints = Vec::with_capacity(c.lits.len() + 1);
+            for l in c.lits.iter() {
+                if asg.assigned(*l) != Some(false) || asg.level(l.vi()) != 0 {
+                    continue 'next;
+                }
+                match self.unit_proof_id[usize::from(!*l)] {
+                    0 => continue 'next,
+                    id => hints.push(id),
+                }
+            }
+            hints.push(c.proof_id);
+            self.certificate_add(&[], &hints);
+            return;
+        }
+        self.certificate_add(&[], &[]);
+    }
+    fn unit_proof_id(&self, l: Lit) -> usize {
+        self.unit_proof_id[usize::from(l)]
+    }
+    fn set_unit_proof_id(&mut self, l: Lit, proof_id: usize) {
+        self.unit_proof_id[usize::from(l)] = proof_id;
     }
-    fn certificate_delete(&mut self, vec: &[Lit]) {
-        if !self.certified.is_empty() && !self.during_vivification {
-            let temp = vec.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
-            self.certified.push((CertifiedRecord::DELETE, temp));
+    fn derive_hints<A>(&mut self, asg: &A, vec: &[Lit], cid: ClauseId) -> Option<Vec<usize>>
+    where
+        A: AssignIF,
+    {
+        let mut marked: Vec<VarId> = Vec::new();
+        let result = self.trace_hints(asg, vec, cid, &mut marked);
+        for vi in marked {
+            self.hint_temp[vi] = 0;
         }
+        result
     }
     fn touch_var(&mut self, vi: VarId) {
         self.touched[Lit::from_assign(vi, true)] = true;
@@ -694,7 +716,7 @@ impl ClauseDBIF for ClauseDB {
         }
         None
     }
-    fn strengthen(&mut self, cid: ClauseId, p: Lit) -> bool {
+    fn strengthen(&mut self, cid: ClauseId, p: Lit, hint: usize) -> bool {
         debug_assert!(!self[cid].is(Flag::DEAD));
         debug_assert!(1 < self[cid].len());
         let c = &mut self[cid];
@@ -745,6 +767,14 @@ impl ClauseDBIF for ClauseDB {
                 self.bin_watcher[!r].register(q, cid);
             }
         }
+        if self.certified.is_active() {
+            let old_id = self.clause[cid.ordinal as usize].proof_id;
+            let mut lits = self.clause[cid.ordinal as usize].lits.clone();
+            self.clause[cid.ordinal as usize].proof_id = self.next_proof_id;
+            self.certificate_add(&lits, &[hint, old_id]);
+            lits.push(p);
+            self.certificate_delete(old_id, &lits);
+        }
         false
     }
     fn minimize_with_biclauses<A>(&mut self, asg: &A, vec: &mut Vec<Lit>)
@@ -776,14 +806,239 @@ impl ClauseDBIF for ClauseDB {
             vec.retain(|l| self.lbd_temp[l.vi()] == key);
         }
     }
-    #[cfg(feature = "incremental_solver")]
-    fn make_permanent_immortal(&mut self, cid: ClauseId) {
-        self.eliminated_permanent
-            .push(self.clause[cid.ordinal as usize].lits.clone());
-    }
 }
 
 impl ClauseDB {
+    /// allocate a new clause without any certification.
+    fn register_clause<A>(
+        &mut self,
+        asg: &mut A,
+        vec: &mut Vec<Lit>,
+        mut learnt: bool,
+        level_sort: bool,
+        proof_id: usize,
+    ) -> ClauseId
+    where
+        A: AssignIF,
+    {
+        let reward = self.activity_inc;
+        if level_sort {
+            #[cfg(feature = "boundary_check")]
+            debug_assert!(1 < vec.len());
+            // // sort literals
+            // let mut i_max = 1;
+            // let mut lv_max = 0;
+            // // seek a literal with max level
+            // let level = asg.level_ref();
+            // for (i, l) in vec.iter().enumerate() {
+            //     let vi = l.vi();
+            //     let lv = level[vi];
+            //     if asg.assign(vi).is_some() && lv_max < lv {
+            //         i_max = i;
+            //         lv_max = lv;
+            //     }
+            // }
+            // vec.swap(1, i_max);
+        }
+        let cid;
+        let l0 = vec[0];
+        let l1 = vec[1];
+        if let Some(w) = self.watcher[!NULL_LIT].pop() {
+            cid = w.c;
+            let c = &mut self[cid];
+            // if !c.is(Flag::DEAD) {
+            //     println!("{} {:?}", cid.format(), vec2int(&c.lits));
+            //     println!("len {}", self.watcher[NULL_LIT.negate() as usize].len());
+            //     for w in &self.watcher[NULL_LIT.negate() as usize][..10] {
+            //         if !self.clause[w.c].is(Flag::DEAD) {
+            //             println!("{}", w.c.format());
+            //         }
+            //     }
+            //     panic!("done");
+            // }
+            debug_assert!(c.is(Flag::DEAD));
+            c.flags = Flag::empty();
+            debug_assert!(c.lits.is_empty()); // c.lits.clear();
+            std::mem::swap(&mut c.lits, vec);
+            c.reward = reward;
+            c.search_from = 2;
+            c.proof_id = proof_id;
+        } else {
+            cid = ClauseId::from(self.clause.len());
+            let mut c = Clause {
+                flags: Flag::empty(),
+                reward,
+                proof_id,
+                ..Clause::default()
+            };
+            std::mem::swap(&mut c.lits, vec);
+            self.clause.push(c);
+        };
+        if self.during_vivification {
+            self[cid].turn_on(Flag::VIV_ASSUMED);
+        }
+        {
+            let ClauseDB {
+                ref mut clause,
+                ref mut lbd_temp,
+                ref mut num_active,
+                ref mut num_bi_clause,
+                ref mut num_bi_learnt,
+                ref mut num_lbd2,
+                ref mut num_learnt,
+                ref mut bin_watcher,
+                ref mut watcher,
+                ..
+            } = self;
+            let c = &mut clause[cid.ordinal as usize];
+            c.update_lbd(asg, lbd_temp);
+            let len2 = c.lits.len() == 2;
+            if c.lits.len() <= 2 || (self.use_chan_seok && c.rank as usize <= self.co_lbd_bound) {
+                learnt = false;
+            }
+            if learnt {
+                c.turn_on(Flag::LEARNT);
+                c.turn_on(Flag::JUST_USED);
+
+                if len2 {
+                    *num_bi_learnt += 1;
+                }
+                if c.rank <= 2 {
+                    *num_lbd2 += 1;
+                }
+                *num_learnt += 1;
+            }
+            if len2 {
+                *num_bi_clause += 1;
+                bin_watcher[!l0].register(l1, cid);
+                bin_watcher[!l1].register(l0, cid);
+            } else {
+                watcher[!l0].register(l1, cid);
+                watcher[!l1].register(l0, cid);
+            }
+            *num_active += 1;
+        }
+        cid
+    }
+    /// trace the implication graph from the conflict by `cid` back to the negation of `vec`,
+    /// and return the proof ids in the order of unit propagation.
+    /// `marked` collects the vars which `hint_temp` should be cleared.
+    fn trace_hints<A>(
+        &mut self,
+        asg: &A,
+        vec: &[Lit],
+        cid: ClauseId,
+        marked: &mut Vec<VarId>,
+    ) -> Option<Vec<usize>>
+    where
+        A: AssignIF,
+    {
+        const IN: u8 = 1;
+        const NEEDED: u8 = 2;
+        const DONE: u8 = 4;
+        const BIN: u8 = 8;
+        let ClauseDB {
+            ref clause,
+            ref bin_watcher,
+            ref unit_proof_id,
+            ref mut hint_temp,
+            ..
+        } = self;
+        let conflict_id = clause[cid.ordinal as usize].proof_id;
+        if conflict_id == 0 {
+            return None;
+        }
+        for l in vec {
+            if asg.assigned(*l) != Some(false) {
+                return None;
+            }
+            hint_temp[l.vi()] = IN;
+            marked.push(l.vi());
+        }
+        // A binary clause with the first literal propagates without any other reason.
+        // This covers the literals removed by `minimize_with_biclauses`.
+        let mut bins: Vec<(Lit, usize)> = Vec::new();
+        if let Some(l0) = vec.first() {
+            for w in &bin_watcher[usize::from(!*l0)] {
+                let c = &clause[w.c.ordinal as usize];
+                let other = c[(c[0] == *l0) as usize];
+                if hint_temp[other.vi()] == 0 && asg.assigned(other) == Some(true) {
+                    hint_temp[other.vi()] = BIN;
+                    marked.push(other.vi());
+                    bins.push((other, c.proof_id));
+                }
+            }
+        }
+        let mut units: Vec<usize> = Vec::new();
+        let mut front: Vec<usize> = Vec::new();
+        let mut chain: Vec<usize> = Vec::new();
+        let mut todo: Vec<Lit> = clause[cid.ordinal as usize].lits.clone();
+        let mut pending = 0;
+        let mut ti = asg.stack_len();
+        loop {
+            while let Some(q) = todo.pop() {
+                let vi = q.vi();
+                let mark = hint_temp[vi];
+                if mark & (IN | NEEDED | DONE) != 0 {
+                    continue;
+                }
+                if mark == 0 {
+                    marked.push(vi);
+                }
+                let bin = if mark == BIN {
+                    bins.iter()
+                        .find(|(b, id)| *b == !q && *id != 0)
+                        .map(|(_, id)| *id)
+                } else {
+                    None
+                };
+                if asg.level(vi) == 0 {
+                    match unit_proof_id[usize::from(!q)] {
+                        0 => return None,
+                        id => units.push(id),
+                    }
+                    hint_temp[vi] = DONE;
+                } else if let Some(id) = bin {
+                    front.push(id);
+                    hint_temp[vi] = DONE;
+                } else {
+                    hint_temp[vi] = NEEDED;
+                    pending += 1;
+                }
+            }
+            if pending == 0 {
+                break;
+            }
+            let vi = loop {
+                if ti == 0 {
+                    return None;
+                }
+                ti -= 1;
+                let vi = asg.stack(ti).vi();
+                if hint_temp[vi] == NEEDED {
+                    break vi;
+                }
+            };
+            hint_temp[vi] = DONE;
+            pending -= 1;
+            match asg.reason(vi) {
+                AssignReason::Implication(r, _) => {
+                    let c = &clause[r.ordinal as usize];
+                    if c.proof_id == 0 {
+                        return None;
+                    }
+                    chain.push(c.proof_id);
+                    todo.extend(c.lits.iter().filter(|q| q.vi() != vi));
+                }
+                AssignReason::None => return None,
+            }
+        }
+        chain.reverse();
+        units.append(&mut front);
+        units.append(&mut chain);
+        units.push(conflict_id);
+        Some(units)
+    }
     /// halve the number of 'learnt' or *removable* clauses.
     fn reduce<A>(&mut self, asg: &A)
     where
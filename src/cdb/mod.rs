@@ -4,6 +4,10 @@ mod cid;
 mod clause;
 /// methods on `ClauseDB`
 mod db;
+/// DRAT and LRAT proof output
+mod proof;
+/// stochastic local search
+mod sls;
 /// methods on `Watch` and `WatchDB`
 mod watch;
 
@@ -11,24 +15,13 @@ pub use self::{
     cid::ClauseIdIF,
     clause::ClauseIF,
     db::ClauseDBIF,
+    proof::{CertifiedRecord, ProofBuffer, ProofFile, ProofRecord, ProofSink, ProofSinkIF},
+    sls::StochasticLocalSearchIF,
     watch::{Watch, WatchDBIF},
 };
 
 use crate::types::*;
 
-/// Record of clause operations to build DRAT certifications.
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
-pub enum CertifiedRecord {
-    /// placed at the end.
-    SENTINEL,
-    /// added a (learnt) clause.
-    ADD,
-    /// deleted a clause.
-    DELETE,
-}
-
-type DRAT = Vec<(CertifiedRecord, Vec<i32>)>;
-
 /// Clause identifier, or clause index, starting with one.
 /// Note: ids are re-used after 'garbage collection'.
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -57,6 +50,8 @@ pub struct Clause {
     reward: f64,
     /// Flags
     flags: Flag,
+    /// the id in certification, which is never re-used. Zero means it isn't certified.
+    pub proof_id: usize,
 }
 
 /// Clause database
@@ -74,8 +69,16 @@ pub struct ClauseDB {
     pub bin_watcher: Vec<Vec<Watch>>,
     /// container of watch literals
     pub watcher: Vec<Vec<Watch>>,
-    /// clause history to make certification
-    pub certified: DRAT,
+    /// the sink of clause history to make certification
+    pub certified: ProofSink,
+    /// the number of given clauses, which ids are from 1 to it
+    num_given: usize,
+    /// the id for the next clause added to certification
+    next_proof_id: usize,
+    /// the ids of unit clauses in certification, indexed by literal
+    unit_proof_id: Vec<usize>,
+    /// a working buffer for hint derivation
+    hint_temp: Vec<u8>,
     /// a number of clauses to emit out-of-memory exception
     soft_limit: usize,
     /// flag for Chan Seok heuristics; this value is exported with `Export:mode`
@@ -137,11 +140,6 @@ pub struct ClauseDB {
     //## vivification
     //
     pub during_vivification: bool,
-
-    //
-    //## incremental solving
-    //
-    pub eliminated_permanent: Vec<Vec<Lit>>,
 }
 
 #[cfg(test)]
@@ -179,14 +177,26 @@ mod tests {
         asg.assign_by_decision(lit(1));
         asg.assign_by_decision(lit(-2));
 
-        let c1 = cdb.new_clause(&mut asg, &mut vec![lit(1), lit(2), lit(3)], false, false);
+        let c1 = cdb.new_clause(
+            &mut asg,
+            &mut vec![lit(1), lit(2), lit(3)],
+            false,
+            false,
+            &[],
+        );
         let c = &cdb[c1];
         assert_eq!(c.rank, 2);
         assert!(!c.is(Flag::DEAD));
         assert!(!c.is(Flag::LEARNT));
         assert!(!c.is(Flag::JUST_USED));
 
-        let c2 = cdb.new_clause(&mut asg, &mut vec![lit(-1), lit(2), lit(3)], true, true);
+        let c2 = cdb.new_clause(
+            &mut asg,
+            &mut vec![lit(-1), lit(2), lit(3)],
+            true,
+            true,
+            &[],
+        );
         let c = &cdb[c2];
         assert_eq!(c.rank, 2);
         assert!(!c.is(Flag::DEAD));
@@ -202,8 +212,14 @@ mod tests {
         };
         let mut asg = AssignStack::instantiate(&config, &cnf);
         let mut cdb = ClauseDB::instantiate(&config, &cnf);
-        let c1 = cdb.new_clause(&mut asg, &mut vec![lit(1), lit(2), lit(3)], false, false);
-        let c2 = cdb.new_clause(&mut asg, &mut vec![lit(-1), lit(4)], false, false);
+        let c1 = cdb.new_clause(
+            &mut asg,
+            &mut vec![lit(1), lit(2), lit(3)],
+            false,
+            false,
+            &[],
+        );
+        let c2 = cdb.new_clause(&mut asg, &mut vec![lit(-1), lit(4)], false, false, &[]);
         cdb[c2].reward = 2.4;
         assert_eq!(c1, c1);
         assert_eq!(c1 == c1, true);
@@ -211,6 +227,34 @@ mod tests {
         assert_eq!(cdb.activity(c2), 2.4);
     }
 
+    #[test]
+    fn test_clause_order() {
+        use std::cmp::Ordering;
+        let clause = |rank: u16, reward: f64| Clause {
+            rank,
+            reward,
+            ..Clause::default()
+        };
+        let cs = [
+            clause(2, 0.5),
+            clause(2, 1.0),
+            clause(4, 2.0),
+            clause(6, 0.0),
+        ];
+        for a in cs.iter() {
+            for b in cs.iter() {
+                // `reduce` sorts clauses by this order; `sort_by` requires a total order.
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                assert_eq!(a.partial_cmp(b), Some(a.cmp(b)));
+            }
+        }
+        assert_eq!(cs[1].cmp(&cs[0]), Ordering::Less);
+        assert_eq!(cs[3].cmp(&cs[2]), Ordering::Greater);
+        let mut perm = vec![3, 2, 0, 1];
+        perm.sort_by(|&a, &b| cs[a].cmp(&cs[b]));
+        assert_eq!(perm, vec![1, 0, 2, 3]);
+    }
+
     #[test]
     fn test_clause_iterator() -> () {
         let config = Config::default();
@@ -220,7 +264,13 @@ mod tests {
         };
         let mut asg = AssignStack::instantiate(&config, &cnf);
         let mut cdb = ClauseDB::instantiate(&config, &cnf);
-        let c1 = cdb.new_clause(&mut asg, &mut vec![lit(1), lit(2), lit(3)], false, false);
+        let c1 = cdb.new_clause(
+            &mut asg,
+            &mut vec![lit(1), lit(2), lit(3)],
+            false,
+            false,
+            &[],
+        );
         assert_eq!(cdb[c1][0..].iter().map(|l| i32::from(*l)).sum::<i32>(), 6);
         let mut iter = cdb[c1][0..].into_iter();
         assert_eq!(iter.next(), Some(&lit(1)));
@@ -0,0 +1,419 @@
+use {
+    crate::{types::*, VERSION},
+    std::{
+        fmt,
+        fs::File,
+        io::{BufWriter, Write},
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// API for DRAT/LRAT proof output like [`add`](`crate::cdb::ProofSinkIF::add`), [`delete`](`crate::cdb::ProofSinkIF::delete`) and [`flush`](`crate::cdb::ProofSinkIF::flush`).
+pub trait ProofSinkIF: fmt::Debug + Send {
+    /// record an added clause, which proof id is `id`, with the ids of its antecedents.
+    fn add(&mut self, id: usize, vec: &[Lit], hints: &[usize]);
+    /// record a deleted clause.
+    fn delete(&mut self, id: usize, vec: &[Lit]);
+    /// return `true` if it requires the antecedents of added clauses.
+    fn needs_hints(&self) -> bool {
+        false
+    }
+    /// write out buffered records.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::IOError` if it failed to write records.
+    fn flush(&mut self) -> MaybeInconsistent;
+}
+
+/// Record of clause operations to build DRAT certifications.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CertifiedRecord {
+    /// added a (learnt) clause.
+    ADD,
+    /// deleted a clause.
+    DELETE,
+}
+
+/// The proof sink held by `ClauseDB`. It does nothing without a backend or a core tracer.
+/// Note: a clone of it has no backend because a proof can't be shared by solvers.
+#[derive(Debug, Default)]
+pub struct ProofSink {
+    sink: Option<Box<dyn ProofSinkIF>>,
+    tracer: Option<CoreTracer>,
+}
+
+impl Clone for ProofSink {
+    fn clone(&self) -> Self {
+        ProofSink::default()
+    }
+}
+
+impl<S> From<S> for ProofSink
+where
+    S: ProofSinkIF + 'static,
+{
+    fn from(sink: S) -> Self {
+        ProofSink {
+            sink: Some(Box::new(sink)),
+            tracer: None,
+        }
+    }
+}
+
+impl ProofSink {
+    /// return `true` if it has a backend or a core tracer.
+    pub fn is_active(&self) -> bool {
+        self.sink.is_some() || self.tracer.is_some()
+    }
+    /// return `true` if it requires the antecedents of added clauses.
+    pub fn needs_hints(&self) -> bool {
+        self.tracer.is_some() || matches!(self.sink, Some(ref sink) if sink.needs_hints())
+    }
+    /// start to trace the antecedents of clauses to build an UNSAT core.
+    pub fn trace_core(&mut self) {
+        self.tracer = Some(CoreTracer::default());
+    }
+    /// return the proof ids of the given clauses used to derive the empty clause.
+    /// Return `None` if it isn't tracing or the refutation has an unknown antecedent.
+    pub fn unsat_core(&self) -> Option<Vec<usize>> {
+        self.tracer.as_ref()?.core()
+    }
+    /// record an added clause.
+    pub fn add(&mut self, id: usize, vec: &[Lit], hints: &[usize]) {
+        if let Some(ref mut sink) = self.sink {
+            sink.add(id, vec, hints);
+        }
+        if let Some(ref mut tracer) = self.tracer {
+            tracer.add(id, vec, hints);
+        }
+    }
+    /// record a deleted clause.
+    pub fn delete(&mut self, id: usize, vec: &[Lit]) {
+        if let Some(ref mut sink) = self.sink {
+            sink.delete(id, vec);
+        }
+    }
+    /// write out buffered records.
+    pub fn flush(&mut self) -> MaybeInconsistent {
+        match self.sink {
+            Some(ref mut sink) => sink.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Proof file formats
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ProofFormat {
+    Drat,
+    BinaryDrat,
+    Lrat,
+}
+
+/// A proof sink writing a DRAT or LRAT file incrementally.
+#[derive(Debug)]
+pub struct ProofFile {
+    buf: BufWriter<File>,
+    format: ProofFormat,
+    /// `true` if any write has failed.
+    failed: bool,
+    /// the id of the last added clause, which LRAT deletion lines start with
+    last_id: usize,
+}
+
+impl ProofFile {
+    /// make a proof file for a CNF `input`.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::IOError` if it failed to create the file.
+    pub fn create<S: fmt::Display>(path: &Path, input: S) -> Result<ProofFile, SolverError> {
+        let mut buf = BufWriter::new(File::create(path).map_err(|_| SolverError::IOError)?);
+        buf.write_all(
+            format!("c Proof generated by splr-{} for {}\nc \n", VERSION, input).as_bytes(),
+        )
+        .map_err(|_| SolverError::IOError)?;
+        Ok(ProofFile::new(buf, ProofFormat::Drat))
+    }
+    /// make a proof file in the binary DRAT format, which has no comment.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::IOError` if it failed to create the file.
+    pub fn create_binary(path: &Path) -> Result<ProofFile, SolverError> {
+        let buf = BufWriter::new(File::create(path).map_err(|_| SolverError::IOError)?);
+        Ok(ProofFile::new(buf, ProofFormat::BinaryDrat))
+    }
+    /// make a proof file in the LRAT format, which has no comment.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::IOError` if it failed to create the file.
+    pub fn create_lrat(path: &Path) -> Result<ProofFile, SolverError> {
+        let buf = BufWriter::new(File::create(path).map_err(|_| SolverError::IOError)?);
+        Ok(ProofFile::new(buf, ProofFormat::Lrat))
+    }
+    fn new(buf: BufWriter<File>, format: ProofFormat) -> ProofFile {
+        ProofFile {
+            buf,
+            format,
+            failed: false,
+            last_id: 0,
+        }
+    }
+    fn write_record(&mut self, record: CertifiedRecord, id: usize, vec: &[Lit], hints: &[usize]) {
+        if self.failed {
+            return;
+        }
+        let buf = &mut self.buf;
+        let result = match self.format {
+            ProofFormat::Lrat if record == CertifiedRecord::ADD => {
+                self.last_id = id;
+                (|| {
+                    write!(buf, "{} ", id)?;
+                    for l in vec {
+                        write!(buf, "{} ", i32::from(*l))?;
+                    }
+                    buf.write_all(b"0 ")?;
+                    for h in hints {
+                        write!(buf, "{} ", h)?;
+                    }
+                    buf.write_all(b"0\n")
+                })()
+            }
+            ProofFormat::Lrat => writeln!(buf, "{} d {} 0", self.last_id, id),
+            ProofFormat::BinaryDrat => (|| {
+                buf.write_all(match record {
+                    CertifiedRecord::ADD => b"a",
+                    CertifiedRecord::DELETE => b"d",
+                })?;
+                for l in vec {
+                    // a literal is mapped to 2 * var + sign, then written in 7-bit chunks
+                    // from the least significant one; the MSB flags a following chunk.
+                    let mut u = 2 * l.vi() as u64 + (!bool::from(*l)) as u64;
+                    while 0x7f < u {
+                        buf.write_all(&[(u & 0x7f) as u8 | 0x80])?;
+                        u >>= 7;
+                    }
+                    buf.write_all(&[u as u8])?;
+                }
+                buf.write_all(&[0])
+            })(),
+            ProofFormat::Drat => (|| {
+                if record == CertifiedRecord::DELETE {
+                    buf.write_all(b"d ")?;
+                }
+                for l in vec {
+                    write!(buf, "{} ", i32::from(*l))?;
+                }
+                buf.write_all(b"0\n")
+            })(),
+        };
+        self.failed = result.is_err();
+    }
+}
+
+impl ProofSinkIF for ProofFile {
+    fn add(&mut self, id: usize, vec: &[Lit], hints: &[usize]) {
+        self.write_record(CertifiedRecord::ADD, id, vec, hints);
+    }
+    fn delete(&mut self, id: usize, vec: &[Lit]) {
+        self.write_record(CertifiedRecord::DELETE, id, vec, &[]);
+    }
+    fn needs_hints(&self) -> bool {
+        self.format == ProofFormat::Lrat
+    }
+    fn flush(&mut self) -> MaybeInconsistent {
+        if self.failed || self.buf.flush().is_err() {
+            self.failed = true;
+            return Err(SolverError::IOError);
+        }
+        Ok(())
+    }
+}
+
+/// A proof step: an addition or a deletion of a clause in DIMACS literals.
+pub type ProofRecord = (CertifiedRecord, Vec<i32>);
+
+/// An in-memory proof sink. Its clones share the same records,
+/// so you can keep one to read the proof after giving another to a solver.
+#[derive(Clone, Debug, Default)]
+pub struct ProofBuffer {
+    records: Arc<Mutex<Vec<ProofRecord>>>,
+}
+
+impl ProofBuffer {
+    /// return a copy of the records.
+    pub fn records(&self) -> Vec<ProofRecord> {
+        self.records.lock().expect("poisoned proof buffer").clone()
+    }
+    fn push(&mut self, r: CertifiedRecord, vec: &[Lit]) {
+        let temp = vec.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
+        self.records
+            .lock()
+            .expect("poisoned proof buffer")
+            .push((r, temp));
+    }
+}
+
+impl ProofSinkIF for ProofBuffer {
+    fn add(&mut self, _id: usize, vec: &[Lit], _hints: &[usize]) {
+        self.push(CertifiedRecord::ADD, vec);
+    }
+    fn delete(&mut self, _id: usize, vec: &[Lit]) {
+        self.push(CertifiedRecord::DELETE, vec);
+    }
+    fn flush(&mut self) -> MaybeInconsistent {
+        Ok(())
+    }
+}
+
+/// A recorder of the antecedents of added clauses.
+/// The given clauses reachable from the empty clause make an UNSAT core.
+#[derive(Debug, Default)]
+struct CoreTracer {
+    /// antecedents of the added clauses
+    hints: Vec<usize>,
+    /// the range of the antecedents of each clause in `hints`; `None` for the given ones
+    range: Vec<Option<(usize, usize)>>,
+    /// the proof id of the empty clause
+    refutation: Option<usize>,
+}
+
+impl CoreTracer {
+    fn add(&mut self, id: usize, vec: &[Lit], hints: &[usize]) {
+        if self.range.len() <= id {
+            self.range.resize(id + 1, None);
+        }
+        let start = self.hints.len();
+        self.hints.extend_from_slice(hints);
+        self.range[id] = Some((start, self.hints.len()));
+        if vec.is_empty() && self.refutation.is_none() {
+            self.refutation = Some(id);
+        }
+    }
+    fn core(&self) -> Option<Vec<usize>> {
+        let mut core = Vec::new();
+        let mut seen = vec![false; self.range.len()];
+        let mut stack = vec![self.refutation?];
+        while let Some(id) = stack.pop() {
+            match self.range.get(id).copied().flatten() {
+                None => core.push(id),
+                // A clause without antecedents can't be traced.
+                Some((start, end)) if start == end => return None,
+                Some((start, end)) => {
+                    for h in self.hints[start..end].iter() {
+                        if *h == 0 {
+                            return None;
+                        }
+                        // An antecedent is always older than its consequence.
+                        if !seen[*h] {
+                            seen[*h] = true;
+                            stack.push(*h);
+                        }
+                    }
+                }
+            }
+        }
+        core.sort_unstable();
+        core.dedup();
+        Some(core)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lits(v: &[i32]) -> Vec<Lit> {
+        v.iter().map(|i| Lit::from(*i)).collect()
+    }
+
+    #[test]
+    fn test_proof_buffer() {
+        let buffer = ProofBuffer::default();
+        let mut sink = ProofSink::from(buffer.clone());
+        assert!(sink.is_active());
+        sink.add(4, &lits(&[1, -2]), &[1, 2]);
+        sink.delete(3, &lits(&[1, -2, 3]));
+        assert_eq!(
+            buffer.records(),
+            vec![
+                (CertifiedRecord::ADD, vec![1, -2]),
+                (CertifiedRecord::DELETE, vec![1, -2, 3]),
+            ]
+        );
+        assert!(!sink.clone().is_active());
+    }
+
+    #[test]
+    fn test_core_tracer() {
+        let mut sink = ProofSink::default();
+        assert_eq!(sink.unsat_core(), None);
+        sink.trace_core();
+        assert!(sink.needs_hints());
+        // given clauses 1 to 5; 2 isn't used.
+        sink.add(6, &lits(&[1]), &[1, 3]);
+        sink.add(7, &lits(&[-2]), &[6, 4]);
+        sink.add(8, &lits(&[3]), &[2]);
+        assert_eq!(sink.unsat_core(), None);
+        sink.add(9, &[], &[6, 7, 5]);
+        assert_eq!(sink.unsat_core(), Some(vec![1, 3, 4, 5]));
+        let mut sink = ProofSink::default();
+        sink.trace_core();
+        sink.add(6, &lits(&[1]), &[]);
+        sink.add(7, &[], &[6, 2]);
+        assert_eq!(sink.unsat_core(), None);
+    }
+
+    #[test]
+    fn test_proof_file() {
+        let path = std::env::temp_dir().join(format!("splr-proof-{}.drat", std::process::id()));
+        let mut sink = ProofFile::create(&path, "test").expect("can't create");
+        sink.add(4, &lits(&[1, -2]), &[1, 2]);
+        sink.delete(3, &lits(&[1, -2, 3]));
+        sink.add(5, &[], &[]);
+        assert!(sink.flush().is_ok());
+        let proof = std::fs::read_to_string(&path).expect("can't read");
+        std::fs::remove_file(&path).expect("can't remove");
+        assert_eq!(
+            proof
+                .lines()
+                .filter(|l| !l.starts_with('c'))
+                .collect::<Vec<_>>(),
+            vec!["1 -2 0", "d 1 -2 3 0", "0"]
+        );
+    }
+
+    #[test]
+    fn test_binary_proof_file() {
+        let path = std::env::temp_dir().join(format!("splr-proof-{}.bdrat", std::process::id()));
+        let mut sink = ProofFile::create_binary(&path).expect("can't create");
+        sink.add(4, &lits(&[1, -2]), &[]);
+        sink.delete(3, &lits(&[-63, 64]));
+        sink.add(5, &[], &[]);
+        assert!(sink.flush().is_ok());
+        let proof = std::fs::read(&path).expect("can't read");
+        std::fs::remove_file(&path).expect("can't remove");
+        assert_eq!(proof, vec![b'a', 2, 5, 0, b'd', 127, 128, 1, 0, b'a', 0]);
+    }
+
+    #[test]
+    fn test_lrat_proof_file() {
+        let path = std::env::temp_dir().join(format!("splr-proof-{}.lrat", std::process::id()));
+        let mut sink = ProofFile::create_lrat(&path).expect("can't create");
+        assert!(sink.needs_hints());
+        sink.add(4, &lits(&[1, -2]), &[1, 2]);
+        sink.delete(3, &lits(&[1, -2, 3]));
+        sink.add(5, &[], &[4, 1]);
+        assert!(sink.flush().is_ok());
+        let proof = std::fs::read_to_string(&path).expect("can't read");
+        std::fs::remove_file(&path).expect("can't remove");
+        assert_eq!(
+            proof.lines().collect::<Vec<_>>(),
+            vec!["4 1 -2 0 1 2 0", "4 d 3 0", "5 0 4 1 0"]
+        );
+    }
+}
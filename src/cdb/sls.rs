@@ -0,0 +1,196 @@
+/// Stochastic local search over the irredundant clauses, in the style of ProbSAT
+use {
+    super::ClauseDB,
+    crate::{assign::AssignIF, types::*},
+    std::collections::HashMap,
+};
+
+/// the exponent of the polynomial break score function
+const CB: f64 = 2.3;
+/// the offset of the polynomial break score function
+const EPS: f64 = 1.0;
+/// the size of the precomputed score table
+const SCORE_TABLE_SIZE: usize = 64;
+
+/// API for stochastic local search
+pub trait StochasticLocalSearchIF {
+    /// search a better assignment from `phases` within `limit` flips by ProbSAT.
+    /// Vars assigned in `asg` are fixed. `phases` is updated to the best assignment found.
+    /// Return the numbers of falsified clauses under the given and the best assignments.
+    ///
+    /// # CAVEAT
+    /// *precondition*: decision level == root level.
+    fn stochastic_local_search<A>(
+        &self,
+        asg: &A,
+        phases: &mut HashMap<VarId, bool>,
+        limit: usize,
+    ) -> (usize, usize)
+    where
+        A: AssignIF;
+}
+
+impl StochasticLocalSearchIF for ClauseDB {
+    fn stochastic_local_search<A>(
+        &self,
+        asg: &A,
+        phases: &mut HashMap<VarId, bool>,
+        limit: usize,
+    ) -> (usize, usize)
+    where
+        A: AssignIF,
+    {
+        let nv = asg.var_stats().0;
+        let mut value: Vec<bool> = vec![false; nv + 1];
+        let mut fixed: Vec<bool> = vec![false; nv + 1];
+        for vi in 1..=nv {
+            if let Some(b) = asg.assign(vi) {
+                value[vi] = b;
+                fixed[vi] = true;
+            } else if let Some(b) = phases.get(&vi) {
+                value[vi] = *b;
+            } else {
+                value[vi] = asg.var(vi).is(Flag::PHASE);
+            }
+        }
+        let mut clauses: Vec<&[Lit]> = Vec::new();
+        let mut occurs: Vec<Vec<usize>> = vec![Vec::new(); 2 * (nv + 1)];
+        let mut num_true: Vec<usize> = Vec::new();
+        for c in self.clause.iter().skip(1) {
+            if c.is(Flag::DEAD) || c.is(Flag::LEARNT) {
+                continue;
+            }
+            if c.lits
+                .iter()
+                .any(|l| fixed[l.vi()] && value[l.vi()] == bool::from(*l))
+            {
+                continue;
+            }
+            let ci = clauses.len();
+            for l in c.lits.iter() {
+                occurs[usize::from(*l)].push(ci);
+            }
+            num_true.push(
+                c.lits
+                    .iter()
+                    .filter(|l| value[l.vi()] == bool::from(**l))
+                    .count(),
+            );
+            clauses.push(&c.lits);
+        }
+        let mut falsified: Vec<usize> = Vec::new();
+        let mut position: Vec<usize> = vec![0; clauses.len()];
+        for (ci, n) in num_true.iter().enumerate() {
+            if *n == 0 {
+                position[ci] = falsified.len();
+                falsified.push(ci);
+            }
+        }
+        let start = falsified.len();
+        let mut best = start;
+        let mut best_value = value.clone();
+        let score = (0..SCORE_TABLE_SIZE)
+            .map(|b| (EPS + b as f64).powf(-CB))
+            .collect::<Vec<_>>();
+        let mut rng = Xorshift::new(0x9E37_79B9_7F4A_7C15 ^ (start * nv + limit) as u64);
+        let mut candidates: Vec<(VarId, f64)> = Vec::new();
+        for _ in 0..limit {
+            if falsified.is_empty() {
+                break;
+            }
+            let ci = falsified[rng.next_usize(falsified.len())];
+            candidates.clear();
+            let mut sum = 0.0;
+            for l in clauses[ci].iter() {
+                let vi = l.vi();
+                if fixed[vi] {
+                    continue;
+                }
+                // flipping `vi` falsifies the clauses in which it is the only true literal.
+                let lit = Lit::from_assign(vi, value[vi]);
+                let breaks = occurs[usize::from(lit)]
+                    .iter()
+                    .filter(|cj| num_true[**cj] == 1)
+                    .count();
+                let s = score[breaks.min(SCORE_TABLE_SIZE - 1)];
+                sum += s;
+                candidates.push((vi, s));
+            }
+            if candidates.is_empty() {
+                // This clause is falsified at root level. Nothing to do.
+                break;
+            }
+            let mut threshold = rng.next_f64() * sum;
+            let mut vi = candidates[candidates.len() - 1].0;
+            for (v, s) in candidates.iter() {
+                if threshold < *s {
+                    vi = *v;
+                    break;
+                }
+                threshold -= *s;
+            }
+            let lit = Lit::from_assign(vi, value[vi]);
+            value[vi] = !value[vi];
+            for cj in occurs[usize::from(lit)].iter() {
+                num_true[*cj] -= 1;
+                if num_true[*cj] == 0 {
+                    position[*cj] = falsified.len();
+                    falsified.push(*cj);
+                }
+            }
+            for cj in occurs[usize::from(!lit)].iter() {
+                num_true[*cj] += 1;
+                if num_true[*cj] == 1 {
+                    let i = position[*cj];
+                    let last = falsified.swap_remove(i);
+                    debug_assert_eq!(last, *cj);
+                    if i < falsified.len() {
+                        position[falsified[i]] = i;
+                    }
+                }
+            }
+            if falsified.len() < best {
+                best = falsified.len();
+                best_value.copy_from_slice(&value);
+            }
+        }
+        for vi in 1..=nv {
+            if !fixed[vi] && !asg.var(vi).is(Flag::ELIMINATED) {
+                phases.insert(vi, best_value[vi]);
+            }
+        }
+        (start, best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assign::AssignStack, cdb::ClauseDBIF};
+
+    #[test]
+    fn test_stochastic_local_search() {
+        let config = Config::default();
+        let cnf = CNFDescription {
+            num_of_variables: 4,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let mut cdb = ClauseDB::instantiate(&config, &cnf);
+        for c in &[[1, 2, 3], [-1, -2, 4], [-3, -4, 1], [2, -3, -4], [-1, 3, 4]] {
+            let mut lits = c.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+            let pid = cdb.given_proof_id();
+            cdb.new_given_clause(&mut asg, &mut lits, pid);
+        }
+        let mut phases: HashMap<VarId, bool> = HashMap::new();
+        for vi in 1..=4 {
+            phases.insert(vi, false);
+        }
+        let (start, best) = cdb.stochastic_local_search(&asg, &mut phases, 1000);
+        assert_eq!(start, 1);
+        assert_eq!(best, 0);
+        for c in cdb.iter().skip(1).filter(|c| !c.is(Flag::DEAD)) {
+            assert!(c.lits.iter().any(|l| phases[&l.vi()] == bool::from(*l)));
+        }
+    }
+}
@@ -1,5 +1,8 @@
 /// Crate `config` provides solver's configuration and CLI.
-use {crate::types::DecisionLevel, std::path::PathBuf};
+use {
+    crate::{assign::RephaseTarget, solver::RestartMode, types::DecisionLevel},
+    std::{convert::TryFrom, path::PathBuf},
+};
 
 /// Configuration built from command line options
 #[derive(Clone, Debug)]
@@ -11,11 +14,29 @@ pub struct Config {
     /// Strategy adaptation switch
     a_adaptive: i32,
 
+    /// Conflict Correlation evaluator switch
+    a_acc: i32,
+
+    /// Blocked clause elimination switch: 2 for covered clause elimination too
+    a_bce: i32,
+
     /// Eliminator switch
     a_elim: i32,
 
-    /// Use Luby series forcibly
-    a_luby: i32,
+    /// Equivalent literal substitution switch
+    a_equiv: i32,
+
+    /// EVSIDS switch; LR (Learning Rate Based Branching) is used otherwise
+    a_evsids: i32,
+
+    /// Luby series for blocked restart intervals
+    a_luby_blocking: i32,
+
+    /// Maximum LBD of a Dep. graph evaluator switch
+    a_mld: i32,
+
+    /// Moving var reward decay switch, used in LR
+    a_mvr: i32,
 
     /// Clause reduction switch
     a_reduce: i32,
@@ -23,18 +44,30 @@ pub struct Config {
     /// Re-phase switch
     a_rephase: i32,
 
+    /// Failed literal probing switch
+    a_probe: i32,
+
     /// Reason-Side Rewarding switch
     a_rsr: i32,
 
+    /// Stochastic local search switch
+    a_sls: i32,
+
     /// Stabilization switch
     a_stabilize: i32,
 
     /// Staging
     a_stage: i32,
 
+    /// Target phase switch: 1 for stabilization mode, 2 for focused mode too
+    a_target: i32,
+
     /// Vivification switch
     a_vivify: i32,
 
+    /// VMTF switch: 1 for focused mode, 2 for stabilization mode too
+    a_vmtf: i32,
+
     //
     //## solver configuration
     //
@@ -65,6 +98,9 @@ pub struct Config {
     /// DRAT Cert. filename
     pub io_pfile: PathBuf,
 
+    /// UNSAT core filename
+    pub io_cfile: PathBuf,
+
     /// Result filename/stdout
     pub io_rfile: PathBuf,
 
@@ -77,6 +113,15 @@ pub struct Config {
     /// Writes a DRAT UNSAT certification file
     pub use_certification: bool,
 
+    /// Uses the binary DRAT format for certification
+    pub use_binary_proof: bool,
+
+    /// Uses the LRAT format for certification
+    pub use_lrat: bool,
+
+    /// Traces clause dependencies to build an UNSAT core
+    pub use_core: bool,
+
     /// Uses Glucose-like progress report
     pub use_log: bool,
 
@@ -95,6 +140,9 @@ pub struct Config {
     //
     //## restarter
     //
+    /// Restart policy: dynamic, luby, geometric, or stabilize
+    pub rst_mode: RestartMode,
+
     /// #conflicts between restarts
     pub rst_step: usize,
 
@@ -107,10 +155,12 @@ pub struct Config {
     /// Blocking restart threshold. Originally this was the Glucose's R.
     pub rst_asg_thr: f64,
 
-    #[cfg(feature = "progress_ACC")]
     /// Conflict Correlation threshold
     pub rst_ccc_thr: f64,
 
+    /// Increase rate of geometric restart intervals
+    pub rst_geo_inc: f64,
+
     /// Length of LBD fast EMA
     pub rst_lbd_len: usize,
 
@@ -120,11 +170,9 @@ pub struct Config {
     /// Forcing restart threshold
     pub rst_lbd_thr: f64,
 
-    #[cfg(feature = "progress_MLD")]
     /// Scaling for Maximum LBD of a Dep. graph
     pub rst_mld_scl: f64,
 
-    #[cfg(feature = "progress_MLD")]
     /// Threshold for Maximum LBD of a Dep. graph
     pub rst_mld_thr: f64,
 
@@ -134,12 +182,33 @@ pub struct Config {
     /// Stabilizer expansion scale
     pub rst_stb_exp: f64,
 
+    //
+    //## rephaser
+    //
+    /// Rephase cycle: B(est), F(lipping), I(nverted), O(riginal), R(andom), W(alk)
+    pub rph_cycle: String,
+
+    /// #conflicts between rephasings, growing arithmetically
+    pub rph_int: usize,
+
     //
     //## vivifier
     //
     /// #reduction for next vivification
     pub viv_thr: usize,
 
+    //
+    //## local search
+    //
+    /// #flips per local search
+    pub sls_flips: usize,
+
+    //
+    //## prober
+    //
+    /// #propagations per probing
+    pub prb_budget: usize,
+
     //
     //## staging
     //
@@ -151,14 +220,12 @@ pub struct Config {
     //
     //## var rewarding
     //
-    #[cfg(not(feature = "moving_var_reward_rate"))]
+    /// Var reward decay
     pub vrw_dcy_rat: f64,
 
-    #[cfg(feature = "moving_var_reward_rate")]
     /// Initial var reward decay
     pub vrw_dcy_beg: f64,
 
-    #[cfg(feature = "moving_var_reward_rate")]
     /// Maximum var reward decay
     pub vrw_dcy_end: f64,
 
@@ -172,14 +239,24 @@ impl Default for Config {
             #[cfg(feature = "strategy_adaptation")]
             a_adaptive: 0,
 
+            a_acc: 0,
+            a_bce: 0,
             a_elim: 1,
-            a_luby: 0,
+            a_equiv: 0,
+            a_evsids: 0,
+            a_luby_blocking: 0,
+            a_mld: 0,
+            a_mvr: 0,
             a_reduce: 1,
             a_rephase: 1,
+            a_probe: 0,
             a_rsr: 1,
+            a_sls: 0,
             a_stabilize: 1,
             a_stage: 1,
+            a_target: 0,
             a_vivify: 0,
+            a_vmtf: 0,
 
             c_cbt_thr: 100,
             c_cls_lim: 0,
@@ -190,46 +267,52 @@ impl Default for Config {
             cnf_file: PathBuf::new(),
             io_odir: PathBuf::from("."),
             io_pfile: PathBuf::from("proof.out"),
+            io_cfile: PathBuf::new(),
             io_rfile: PathBuf::new(),
             no_color: false,
             quiet_mode: false,
             use_certification: false,
+            use_binary_proof: false,
+            use_lrat: false,
+            use_core: false,
             use_log: false,
 
             elm_cls_lim: 32,
             elm_grw_lim: 0,
             elm_var_occ: 8192,
 
+            rst_mode: RestartMode::Dynamic,
             rst_step: 24,
             rst_asg_len: 32,
             rst_asg_slw: 10000,
             rst_asg_thr: 0.10,
 
-            #[cfg(feature = "progress_ACC")]
             rst_ccc_thr: 0.7,
+            rst_geo_inc: 1.5,
 
             rst_lbd_len: 32,
             rst_lbd_slw: 8192,
             rst_lbd_thr: 1.20,
 
-            #[cfg(feature = "progress_MLD")]
             rst_mld_scl: 0.10,
-            #[cfg(feature = "progress_MLD")]
             rst_mld_thr: 0.80,
 
             rst_stb_exp: 1.0,
             rst_stb_scl: 2.0,
 
+            sls_flips: 100_000,
+            prb_budget: 200_000,
+
             stg_rwd_dcy: 0.5,
             stg_rwd_val: 1.0,
 
+            rph_cycle: "BWOBWI".to_string(),
+            rph_int: 1000,
+
             viv_thr: 200,
 
-            #[cfg(not(feature = "moving_var_reward_rate"))]
             vrw_dcy_rat: 0.96,
-            #[cfg(feature = "moving_var_reward_rate")]
             vrw_dcy_beg: 0.90,
-            #[cfg(feature = "moving_var_reward_rate")]
             vrw_dcy_end: 0.96,
             vrw_occ_cmp: 0.50,
         }
@@ -238,38 +321,48 @@ impl Default for Config {
 
 impl Config {
     pub fn inject_from_args(&mut self) {
+        let args = std::env::args().skip(1).collect::<Vec<String>>();
+        self.inject_from(&args);
+    }
+    /// set the options given as command line arguments, which don't include the command name.
+    /// The last one is the CNF file if it exists.
+    pub fn inject_from<S: AsRef<str>>(&mut self, args: &[S]) {
         let mut help = false;
         let mut version = false;
-        if let Some(ref cnf) = std::env::args().last() {
-            let path = PathBuf::from(cnf.clone());
+        if let Some(cnf) = args.last() {
+            let path = PathBuf::from(cnf.as_ref());
             if path.exists() {
                 self.cnf_file = path;
             }
         }
-        let args = std::env::args();
-        let mut iter = args.skip(1);
+        let mut iter = args.iter().map(|arg| arg.as_ref());
         while let Some(arg) = iter.next() {
             if let Some(stripped) = arg.strip_prefix("--") {
-                let flags = ["no-color", "quiet", "certify", "log", "help", "version"];
+                let flags = [
+                    "no-color",
+                    "quiet",
+                    "certify",
+                    "binary-proof",
+                    "lrat",
+                    "log",
+                    "help",
+                    "version",
+                ];
                 let options_i32 = [
-                    "ADP", "ELI", "LBY", "RDC", "RPH", "RSR", "STB", "STG", "VIV",
+                    "ACC", "ADP", "BCE", "ELI", "EQV", "EVS", "LBK", "LBY", "MLD", "MVR", "PRB",
+                    "RDC", "RPH", "RSR", "SLS", "STB", "STG", "TGT", "VIV", "VMT",
                 ];
                 let options_u32 = ["cbt"];
                 let options_usize = [
                     "cl", "ii", "stat", "ecl", "evl", "evo", "rs", "ral", "ras", "rll", "rls",
-                    "vit",
+                    "pbg", "rpi", "slf", "vit",
                 ];
-                #[cfg(not(feature = "moving_var_reward_rate"))]
                 let options_f64 = [
-                    "timeout", "rat", "rct", "rlt", "rms", "rmt", "rse", "rss", "srd", "srv",
-                    "vdr", "vro",
+                    "timeout", "rat", "rct", "rgi", "rlt", "rms", "rmt", "rse", "rss", "srd",
+                    "srv", "vdr", "vri", "vrm", "vro",
                 ];
-                #[cfg(feature = "moving_var_reward_rate")]
-                let options_f64 = [
-                    "timeout", "rat", "rct", "rlt", "rms", "rmt", "rse", "rss", "srd", "srv",
-                    "vri", "vrm", "vro",
-                ];
-                let options_path = ["dir", "proof", "result"];
+                let options_str = ["rmd", "rpc"];
+                let options_path = ["dir", "proof", "core", "result"];
                 let seg: Vec<&str> = stripped.split('=').collect();
                 match seg.len() {
                     1 => {
@@ -279,6 +372,8 @@ impl Config {
                                 "no-color" => self.no_color = true,
                                 "quiet" => self.quiet_mode = true,
                                 "certify" => self.use_certification = true,
+                                "binary-proof" => self.use_binary_proof = true,
+                                "lrat" => self.use_lrat = true,
                                 "log" => self.use_log = true,
                                 "help" => help = true,
                                 "version" => version = true,
@@ -291,14 +386,27 @@ impl Config {
                                         #[cfg(feature = "strategy_adaptation")]
                                         "ADP" => self.a_adaptive = val,
 
+                                        "ACC" => self.a_acc = val,
+                                        "BCE" => self.a_bce = val,
                                         "ELI" => self.a_elim = val,
-                                        "LBY" => self.a_luby = val,
+                                        "EQV" => self.a_equiv = val,
+                                        "EVS" => self.a_evsids = val,
+                                        "LBK" => self.a_luby_blocking = val,
+                                        // the old switch for `--rmd luby`
+                                        "LBY" if val != 0 => self.rst_mode = RestartMode::Luby,
+                                        "LBY" => (),
+                                        "MLD" => self.a_mld = val,
+                                        "MVR" => self.a_mvr = val,
+                                        "PRB" => self.a_probe = val,
                                         "RDC" => self.a_reduce = val,
                                         "RPH" => self.a_rephase = val,
                                         "RSR" => self.a_rsr = val,
+                                        "SLS" => self.a_sls = val,
                                         "STB" => self.a_stabilize = val,
                                         "STG" => self.a_stage = val,
+                                        "TGT" => self.a_target = val,
                                         "VIV" => self.a_vivify = val,
+                                        "VMT" => self.a_vmtf = val,
                                         _ => panic!("invalid option: {}", name),
                                     }
                                 } else {
@@ -334,6 +442,9 @@ impl Config {
                                         "ras" => self.rst_asg_slw = val,
                                         "rll" => self.rst_lbd_len = val,
                                         "rls" => self.rst_lbd_slw = val,
+                                        "pbg" => self.prb_budget = val,
+                                        "rpi" => self.rph_int = val,
+                                        "slf" => self.sls_flips = val,
                                         "vit" => self.viv_thr = val,
                                         _ => panic!("invalid option: {}", name),
                                     }
@@ -349,26 +460,17 @@ impl Config {
                                     match name {
                                         "timeout" => self.c_tout = val,
                                         "rat" => self.rst_asg_thr = val,
-
-                                        #[cfg(feature = "progress_ACC")]
                                         "rct" => self.rst_ccc_thr = val,
-
+                                        "rgi" => self.rst_geo_inc = val,
                                         "rlt" => self.rst_lbd_thr = val,
-
-                                        #[cfg(feature = "progress_MLD")]
                                         "rms" => self.rst_mld_scl = val,
-                                        #[cfg(feature = "progress_MLD")]
                                         "rmt" => self.rst_mld_thr = val,
-
                                         "rse" => self.rst_stb_exp = val,
                                         "rss" => self.rst_stb_scl = val,
                                         "srd" => self.stg_rwd_dcy = val,
                                         "srv" => self.stg_rwd_val = val,
-                                        #[cfg(not(feature = "moving_var_reward_rate"))]
                                         "vdr" => self.vrw_dcy_rat = val,
-                                        #[cfg(feature = "moving_var_reward_rate")]
                                         "vri" => self.vrw_dcy_beg = val,
-                                        #[cfg(feature = "moving_var_reward_rate")]
                                         "vrm" => self.vrw_dcy_end = val,
                                         "vro" => self.vrw_occ_cmp = val,
                                         _ => panic!("invalid option: {}", name),
@@ -379,11 +481,35 @@ impl Config {
                             } else {
                                 panic!("no argument for {}", name);
                             }
+                        } else if options_str.contains(&name) {
+                            if let Some(val) = iter.next() {
+                                match name {
+                                    "rmd" => {
+                                        self.rst_mode = val
+                                            .parse::<RestartMode>()
+                                            .unwrap_or_else(|_| panic!("invalid value {}", name))
+                                    }
+                                    "rpc" => {
+                                        if val.chars().any(|c| RephaseTarget::try_from(c).is_err())
+                                        {
+                                            panic!("invalid value {}", name);
+                                        }
+                                        self.rph_cycle = val.to_string();
+                                    }
+                                    _ => panic!("invalid option: {}", name),
+                                }
+                            } else {
+                                panic!("no argument for {}", name);
+                            }
                         } else if options_path.contains(&name) {
                             if let Some(val) = iter.next() {
                                 match name {
                                     "dir" => self.io_odir = PathBuf::from(val),
                                     "proof" => self.io_pfile = PathBuf::from(val),
+                                    "core" => {
+                                        self.io_cfile = PathBuf::from(val);
+                                        self.use_core = true;
+                                    }
                                     "result" => self.io_rfile = PathBuf::from(val),
                                     _ => panic!("invalid option: {}", name),
                                 }
@@ -452,18 +578,31 @@ FLAGS:
   -C, --no-color           Disable coloring
   -q, --quiet              Disable any progress message
   -c, --certify            Writes a DRAT UNSAT certification file
+      --binary-proof       Uses the binary DRAT format for certification
+      --lrat               Uses the LRAT format for certification
   -l, --log                Uses Glucose-like progress report
   -V, --version            Prints version information
 OPTIONS (\x1B[000m\x1B[031mred options depend on features in Cargo.toml\x1B[000m):
+      --ACC <a-acc>        Conflict Correlation evaluator {:>10}
       \x1B[000m\x1B[031m--ADP <a-adaptive>   Strategy adaptation switch     {:>10}\x1B[000m
+      --BCE <a-bce>        BCE (1:blocked, 2:covered)     {:>10}
       --ELI <a-elim>       Eliminator switch              {:>10}
+      --EQV <a-equiv>      Equiv. literal substitution    {:>10}
+      --EVS <a-evsids>     Use EVSIDS instead of LR       {:>10}
+      --LBK <a-luby-blk>   Luby series for blocking       {:>10}
       --LBY <a-luby>       Use Luby series for restart    {:>10}
+      --MLD <a-mld>        Max LBD of Dep. evaluator      {:>10}
+      --MVR <a-mvr>        Moving var reward decay in LR  {:>10}
+      --PRB <a-probe>      Failed literal probing switch  {:>10}
       --RDC <a-reduce>     Clause reduction switch        {:>10}
       --RPH <a-rephase>    Re-phase switch                {:>10}
       --RSR <a-rsr>        Reason-Side Rewarding switch   {:>10}
+      --SLS <a-sls>        Local search switch            {:>10}
       --STB <a-stabilize>  Stabilization switch           {:>10}
       --STG <a-stage>      Stage switch                   {:>10}
+      --TGT <a-target>     Target phase (1:stable, 2:all) {:>10}
       --VIV <a-vivify>     Vivification switch            {:>10}
+      --VMT <a-vmtf>       VMTF (1:focused, 2:always)     {:>10}
       --cbt <c-cbt-thr>    Dec. lvl to use chronoBT       {:>10}
       --cl <c-cls-lim>     Soft limit of #clauses (6MC/GB){:>10}
       --ii <c-ip-int>      #cls to start in-processor     {:>10}
@@ -473,28 +612,37 @@ OPTIONS (\x1B[000m\x1B[031mred options depend on features in Cargo.toml\x1B[000m
       --evo <elm-var-occ>  Max #cls for var elimination   {:>10}
   -o, --dir <io-odir>      Output directory                {:>10}
   -p, --proof <io-pfile>   DRAT Cert. filename                {:>10}
+      --core <io-cfile>    UNSAT core filename                {:>10}
   -r, --result <io-rfile>  Result filename/stdout             {:>10}
       --ral <rst-asg-len>  Length of assign. fast EMA     {:>10}
       --ras <rst-asg-slw>  Length of assign. slow EMA     {:>10}
       --rat <rst-asg-thr>  Blocking restart threshold        {:>10.2}
-      \x1B[000m\x1B[031m--rct <rst-ccc-thr>  Conflict Correlation threshold    {:>10.2}\x1B[000m
+      --rct <rst-ccc-thr>  Conflict Correlation threshold    {:>10.2}
+      --rgi <rst-geo-inc>  Geometric restart increase rate   {:>10.2}
       --rll <rst-lbd-len>  Length of LBD fast EMA         {:>10}
       --rls <rst-lbd-slw>  Length of LBD slow EMA         {:>10}
       --rlt <rst-lbd-thr>  Forcing restart threshold         {:>10.2}
-      \x1B[000m\x1B[031m--rms <rst-mld-scl>  Scaling for Max LBD of Dep.       {:>10.2}\x1B[000m
-      \x1B[000m\x1B[031m--rmt <rst-mld-thr>  Threshold for Max LBD of Dep.     {:>10.2}\x1B[000m
+      --rmd <rst-mode>     Restart policy                 {:>10}
+      --rms <rst-mld-scl>  Scaling for Max LBD of Dep.       {:>10.2}
+      --rmt <rst-mld-thr>  Threshold for Max LBD of Dep.     {:>10.2}
       --rse <rst-stb-exp>  Stabilizer expansion scale        {:>10.2}
       --rss <rst-stb-scl>  Stabilizer scaling                {:>10.2}
       --rs  <rst-step>     #conflicts between restarts    {:>10}
+      --pbg <prb-budget>   #propagations per probing      {:>10}
+      --rpc <rph-cycle>    Rephase cycle (B,F,I,O,R,W)    {:>10}
+      --rpi <rph-int>      #conflicts between rephasings  {:>10}
+      --slf <sls-flips>    #flips per local search        {:>10}
       --srd <stg-rwd-dcy>  Decay rate for staged vare reward {:>10.2}
       --srv <stg-rwd-val>  Extra reward for staged vars      {:>10.2}
       --vit <viv-thr>      #clause to try to vivify       {:>10}
-      \x1B[000m\x1B[031m--vri <vrw-dcy-beg>  Initial var reward decay          {:>10.2}\x1B[000m
-      \x1B[000m\x1B[031m--vrm <vrw-dcy-end>  Maximum var reward decay          {:>10.2}\x1B[000m
-      \x1B[000m\x1B[031m--vro <vrw-occ-cmp>  Occ. compression rate in LR       {:>10.2}\x1B[000m
+      --vdr <vrw-dcy-rat>  Var reward decay                  {:>10.2}
+      --vri <vrw-dcy-beg>  Initial var reward decay w/ MVR   {:>10.2}
+      --vrm <vrw-dcy-end>  Maximum var reward decay w/ MVR   {:>10.2}
+      --vro <vrw-occ-cmp>  Occ. compression rate in LR       {:>10.2}
 ARGS:
   <cnf-file>    DIMACS CNF file
 ",
+        config.a_acc,
         {
             #[cfg(not(feature = "strategy_adaptation"))]
             {
@@ -505,14 +653,24 @@ ARGS:
                 config.a_adaptive
             }
         },
+        config.a_bce,
         config.a_elim,
-        config.a_luby,
+        config.a_equiv,
+        config.a_evsids,
+        config.a_luby_blocking,
+        (config.rst_mode == RestartMode::Luby) as i32,
+        config.a_mld,
+        config.a_mvr,
+        config.a_probe,
         config.a_reduce,
         config.a_rephase,
         config.a_rsr,
+        config.a_sls,
         config.a_stabilize,
         config.a_stage,
+        config.a_target,
         config.a_vivify,
+        config.a_vmtf,
         config.c_cbt_thr,
         config.c_cls_lim,
         config.c_ip_int,
@@ -522,79 +680,33 @@ ARGS:
         config.elm_var_occ,
         config.io_odir.to_string_lossy(),
         config.io_pfile.to_string_lossy(),
+        config.io_cfile.to_string_lossy(),
         config.io_rfile.to_string_lossy(),
         config.rst_asg_len,
         config.rst_asg_slw,
         config.rst_asg_thr,
-        {
-            #[cfg(not(feature = "progress_ACC"))]
-            {
-                0.0
-            }
-            #[cfg(feature = "progress_ACC")]
-            {
-                config.rst_ccc_thr
-            }
-        },
+        config.rst_ccc_thr,
+        config.rst_geo_inc,
         config.rst_lbd_len,
         config.rst_lbd_slw,
         config.rst_lbd_thr,
-        {
-            #[cfg(feature = "progress_MLD")]
-            {
-                config.rst_mld_scl
-            }
-            #[cfg(not(feature = "progress_MLD"))]
-            {
-                0.0
-            }
-        },
-        {
-            #[cfg(feature = "progress_MLD")]
-            {
-                config.rst_mld_thr
-            }
-            #[cfg(not(feature = "progress_MLD"))]
-            {
-                0.0
-            }
-        },
+        config.rst_mode,
+        config.rst_mld_scl,
+        config.rst_mld_thr,
         config.rst_stb_exp,
         config.rst_stb_scl,
         config.rst_step,
+        config.prb_budget,
+        config.rph_cycle,
+        config.rph_int,
+        config.sls_flips,
         config.stg_rwd_dcy,
         config.stg_rwd_val,
         config.viv_thr,
-        {
-            #[cfg(not(feature = "moving_var_reward_rate"))]
-            {
-                0.0
-            }
-            #[cfg(feature = "moving_var_reward_rate")]
-            {
-                config.vrw_dcy_beg
-            }
-        },
-        {
-            #[cfg(not(feature = "moving_var_reward_rate"))]
-            {
-                0.0
-            }
-            #[cfg(feature = "moving_var_reward_rate")]
-            {
-                config.vrw_dcy_end
-            }
-        },
-        {
-            #[cfg(not(feature = "moving_var_reward_rate"))]
-            {
-                0.0
-            }
-            #[cfg(feature = "moving_var_reward_rate")]
-            {
-                config.vrw_occ_cmp
-            }
-        },
+        config.vrw_dcy_rat,
+        config.vrw_dcy_beg,
+        config.vrw_dcy_end,
+        config.vrw_occ_cmp,
     )
 }
 
@@ -629,11 +741,35 @@ impl Config {
     pub fn override_args(mut self) -> Config {
         self
     }
+    pub fn use_bce(&self) -> bool {
+        dispatch!(self.a_bce)
+    }
+    pub fn use_cce(&self) -> bool {
+        1 < self.a_bce
+    }
     pub fn use_elim(&self) -> bool {
         dispatch!(self.a_elim)
     }
-    pub fn use_luby(&self) -> bool {
-        dispatch!(self.a_luby)
+    pub fn use_equiv(&self) -> bool {
+        dispatch!(self.a_equiv)
+    }
+    pub fn use_evsids(&self) -> bool {
+        dispatch!(self.a_evsids)
+    }
+    pub fn use_acc(&self) -> bool {
+        dispatch!(self.a_acc)
+    }
+    pub fn use_luby_blocking(&self) -> bool {
+        dispatch!(self.a_luby_blocking)
+    }
+    pub fn use_mld(&self) -> bool {
+        dispatch!(self.a_mld)
+    }
+    pub fn use_moving_var_reward_rate(&self) -> bool {
+        dispatch!(self.a_mvr)
+    }
+    pub fn use_probe(&self) -> bool {
+        dispatch!(self.a_probe)
     }
     pub fn use_reduce(&self) -> bool {
         dispatch!(self.a_reduce)
@@ -641,12 +777,27 @@ impl Config {
     pub fn use_rephase(&self) -> bool {
         dispatch!(self.a_rephase)
     }
+    pub fn use_target_phases(&self) -> bool {
+        dispatch!(self.a_target)
+    }
+    pub fn use_target_phases_in_focused_mode(&self) -> bool {
+        1 < self.a_target
+    }
     pub fn use_vivify(&self) -> bool {
         dispatch!(self.a_vivify)
     }
+    pub fn use_vmtf(&self) -> bool {
+        dispatch!(self.a_vmtf)
+    }
+    pub fn use_vmtf_in_stable_mode(&self) -> bool {
+        1 < self.a_vmtf
+    }
     pub fn use_reason_side_rewarding(&self) -> bool {
         dispatch!(self.a_rsr)
     }
+    pub fn use_sls(&self) -> bool {
+        dispatch!(self.a_sls)
+    }
     pub fn use_stabilize(&self) -> bool {
         dispatch!(self.a_stabilize)
     }
@@ -11,9 +11,12 @@ It adopts various research results on SAT solvers:
 - pre/in-process simplification based on clause subsumption and variable elimination
 - Chronological backtrack and non-chronological backjump
 - Learning Rate Based Branching and Reason Side Rewarding
-- Rephase
+- Target phases, rephasing and phase initialization by stochastic local search
 - Search stabilization
 - clause vivification
+- failed literal probing
+- equivalent literal substitution
+- blocked and covered clause elimination
 
 *Many thanks to SAT researchers.*
 
@@ -41,6 +44,7 @@ let v: Vec<Vec<i32>> = vec![vec![1, 2], vec![-1, 3], vec![1, -3], vec![-1, 2]];
 match Certificate::try_from(v).expect("panic!") {
     Certificate::UNSAT => 0,
     Certificate::SAT(vec) => vec.len(),
+    Certificate::UNKNOWN => 0,
 };
 ```
 
@@ -52,6 +56,8 @@ This document covers extra functions only if you built it with `cargo doc --feat
 */
 /// Crate `assign` implements Boolean Constraint Propagation and decision var selection.
 pub mod assign;
+/// Crate `capi` provides the IPASIR interface for C programs.
+pub mod capi;
 /// Crate `cdb` provides [`Clause`](`crate::cdb::Clause`) object and its manager [`ClauseDB`](`crate::cdb::ClauseDB`).
 pub mod cdb;
 /// Crate `config` provides solver's configuration and CLI.
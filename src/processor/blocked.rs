@@ -0,0 +1,338 @@
+/// Crate `blocked` implements blocked clause elimination and covered clause elimination.
+use {
+    super::{EliminateIF, Eliminator},
+    crate::{assign::AssignIF, cdb::ClauseDBIF, types::*},
+};
+
+/// remove the clauses which are blocked, or covered if `eliminate_covered_clause` is on.
+/// A removed clause is saved with its witness literal first, so `extend_model` can repair
+/// a model by flipping the witness. A covered clause is saved as the sequence of its
+/// extensions by covered literals, each of which is witnessed by the literal used to
+/// find them. Literals on frozen or assumed vars are never witnesses.
+/// Learnt clauses are neither removed nor checked as they are implied by the others.
+pub fn eliminate_blocked_clauses<A, C>(
+    asg: &mut A,
+    cdb: &mut C,
+    elim: &mut Eliminator,
+    timedout: &mut usize,
+) where
+    A: AssignIF,
+    C: ClauseDBIF,
+{
+    debug_assert_eq!(asg.decision_level(), 0);
+    let mut mark: Vec<bool> = vec![false; 2 * (asg.var_stats().0 + 1)];
+    for ci in 1..cdb.len() {
+        if *timedout == 0 {
+            return;
+        }
+        let cid = ClauseId::from(ci);
+        let c = &cdb[cid];
+        if c.is(Flag::DEAD)
+            || c.is(Flag::LEARNT)
+            || !c.is(Flag::OCCUR_LINKED)
+            || c.lits.iter().any(|l| asg.assign(l.vi()).is_some())
+        {
+            continue;
+        }
+        let mut lits = c.lits.clone();
+        let steps = cover(asg, cdb, elim, cid, &mut lits, &mut mark, timedout);
+        for l in lits.iter() {
+            mark[usize::from(*l)] = false;
+        }
+        if let Some(steps) = steps {
+            #[cfg(feature = "trace_elimination")]
+            println!("# eliminate_blocked_clause {}", cdb[cid]);
+            for (len, witness) in steps.iter() {
+                make_witnessed_clause(&mut elim.elim_lits, *witness, &lits[..*len]);
+                asg.var_mut(witness.vi()).turn_on(Flag::BLOCKING);
+            }
+            cdb.detach(cid);
+            elim.remove_cid_occur(asg, cid, &mut cdb[cid]);
+        }
+    }
+}
+
+/// return the steps to remove clause `cid` if it's blocked or covered.
+/// Each step is a pair of the length of a prefix of `lits` and its witness.
+/// `lits` is extended by covered literals, which are marked with the others.
+fn cover<A, C>(
+    asg: &A,
+    cdb: &C,
+    elim: &Eliminator,
+    cid: ClauseId,
+    lits: &mut Vec<Lit>,
+    mark: &mut [bool],
+    timedout: &mut usize,
+) -> Option<Vec<(usize, Lit)>>
+where
+    A: AssignIF,
+    C: ClauseDBIF,
+{
+    for l in lits.iter() {
+        mark[usize::from(*l)] = true;
+    }
+    let mut steps: Vec<(usize, Lit)> = Vec::new();
+    let mut i = 0;
+    while i < lits.len() {
+        let l = lits[i];
+        i += 1;
+        let v = asg.var(l.vi());
+        let w = &elim[l.vi()];
+        if w.aborted
+            || v.is(Flag::ASSUMED)
+            || v.is(Flag::FROZEN)
+            || v.is(Flag::ELIMINATED)
+            || asg.assign(l.vi()).is_some()
+        {
+            continue;
+        }
+        let partners = if bool::from(l) {
+            &w.neg_occurs
+        } else {
+            &w.pos_occurs
+        };
+        // the literals shared by all the partners making non-tautological resolvents
+        let mut common: Option<Vec<Lit>> = None;
+        for did in partners.iter() {
+            let d = &cdb[*did];
+            if *did == cid || d.is(Flag::DEAD) || d.is(Flag::LEARNT) {
+                continue;
+            }
+            *timedout = timedout.saturating_sub(d.len());
+            if d.lits.iter().any(|k| *k != !l && mark[usize::from(!*k)]) {
+                continue;
+            }
+            match common {
+                Some(ref mut vec) if elim.eliminate_covered_clause => {
+                    vec.retain(|k| d.lits.contains(k));
+                }
+                None if elim.eliminate_covered_clause => {
+                    common = Some(d.lits.iter().filter(|k| **k != !l).copied().collect());
+                }
+                _ => common = Some(Vec::new()),
+            }
+            if matches!(common, Some(ref vec) if vec.is_empty()) {
+                break;
+            }
+        }
+        let added = match common {
+            None => {
+                steps.push((lits.len(), l));
+                return Some(steps);
+            }
+            Some(vec) => vec
+                .into_iter()
+                .filter(|k| !mark[usize::from(*k)])
+                .collect::<Vec<_>>(),
+        };
+        if added.is_empty() {
+            continue;
+        }
+        if elim.eliminate_combination_limit != 0
+            && elim.eliminate_combination_limit < lits.len() + added.len()
+        {
+            return None;
+        }
+        steps.push((lits.len(), l));
+        for k in added {
+            if mark[usize::from(!k)] {
+                // The extended clause is a tautology.
+                return Some(steps);
+            }
+            lits.push(k);
+            mark[usize::from(k)] = true;
+        }
+        if *timedout == 0 {
+            return None;
+        }
+    }
+    None
+}
+
+fn make_witnessed_clause(vec: &mut Vec<Lit>, witness: Lit, lits: &[Lit]) {
+    debug_assert!(lits.contains(&witness));
+    vec.push(witness);
+    for l in lits.iter() {
+        if *l != witness {
+            vec.push(*l);
+        }
+    }
+    vec.push(Lit::from(lits.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assign::{AssignStack, PropagateIF, VarManipulateIF},
+        cdb::ClauseDB,
+        config::Config,
+    };
+
+    fn build(cls: &[Vec<i32>], frozen: &[VarId]) -> (AssignStack, ClauseDB, Eliminator) {
+        let config = Config::default();
+        let cnf = CNFDescription {
+            num_of_variables: 5,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let mut cdb = ClauseDB::instantiate(&config, &cnf);
+        let mut elim = Eliminator::instantiate(&config, &cnf);
+        for vi in frozen.iter() {
+            asg.var_mut(*vi).turn_on(Flag::FROZEN);
+        }
+        for c in cls.iter() {
+            let mut lits = c.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+            let pid = cdb.given_proof_id();
+            cdb.new_given_clause(&mut asg, &mut lits, pid);
+        }
+        elim.enable = true;
+        elim.activate();
+        elim.prepare(&mut asg, &mut cdb, false);
+        (asg, cdb, elim)
+    }
+
+    fn num_alive(cdb: &ClauseDB) -> usize {
+        cdb.iter().skip(1).filter(|c| !c.is(Flag::DEAD)).count()
+    }
+
+    #[test]
+    fn test_blocked_clause_elimination() {
+        // [-1, -2, 4] is blocked on -1.
+        let cls = vec![vec![1, 2], vec![-1, 3], vec![-1, -2, 4]];
+        let (mut asg, mut cdb, mut elim) = build(&cls, &[2, 3, 4]);
+        elim.eliminate_covered_clause = false;
+        let mut timedout = 1_000;
+        eliminate_blocked_clauses(&mut asg, &mut cdb, &mut elim, &mut timedout);
+        assert_eq!(num_alive(&cdb), 2);
+        assert!(cdb[ClauseId::from(3usize)].is(Flag::DEAD));
+        assert!(asg.var(1).is(Flag::BLOCKING));
+        for l in &[1, 2, 3, -4] {
+            asg.assign_by_decision(Lit::from(*l));
+        }
+        let model = asg.extend_model(&mut cdb, elim.eliminated_lits());
+        assert_eq!(model[1], Some(false));
+        // The blocked clause comes back when the witness is frozen.
+        assert_eq!(
+            elim.reactivate(&mut asg, 1),
+            vec![vec![Lit::from(-1), Lit::from(-2), Lit::from(4)]]
+        );
+        assert!(!asg.var(1).is(Flag::BLOCKING));
+        assert!(elim.eliminated_lits().is_empty());
+    }
+
+    #[test]
+    fn test_covered_clause_elimination() {
+        // [1, 2] is covered by 3, then [1, 2, 3] is blocked on 3.
+        let cls = vec![vec![1, 2], vec![-1, 3], vec![-3, -2, 5]];
+        let (mut asg, mut cdb, mut elim) = build(&cls, &[2, 5]);
+        elim.eliminate_covered_clause = false;
+        let mut timedout = 1_000;
+        eliminate_blocked_clauses(&mut asg, &mut cdb, &mut elim, &mut timedout);
+        assert_eq!(num_alive(&cdb), 3);
+        elim.eliminate_covered_clause = true;
+        eliminate_blocked_clauses(&mut asg, &mut cdb, &mut elim, &mut timedout);
+        assert!(cdb[ClauseId::from(1usize)].is(Flag::DEAD));
+        for l in &[-1, -2, -3, -5] {
+            asg.assign_by_decision(Lit::from(*l));
+        }
+        let model = asg.extend_model(&mut cdb, elim.eliminated_lits());
+        for c in cls.iter() {
+            assert!(c
+                .iter()
+                .any(|i| model[i.unsigned_abs() as usize] == Some(0 < *i)));
+        }
+    }
+
+    #[test]
+    fn test_blocked_clause_reactivation() {
+        use crate::solver::{Certificate, SatSolverIF, SolveIF, Solver};
+        use std::convert::TryFrom;
+        let mut config = Config::default();
+        config.inject_from(&["--BCE", "1"]);
+        // A blocked clause is detached but watched until garbage collection,
+        // which mustn't stop var elimination from adding the same resolvent.
+        let cls = vec![
+            vec![5, 6, 1],
+            vec![-4, -3],
+            vec![-7, 4],
+            vec![-2, -3],
+            vec![-2, -7, 5],
+            vec![5, -2, -3],
+            vec![-7, 1, 8],
+            vec![5, -1],
+            vec![1, -8, -7],
+            vec![-5, -8],
+            vec![-8, -2],
+            vec![-2, -1],
+            vec![-6, -2, 5],
+            vec![8, 2],
+            vec![6, 3],
+            vec![-1, 8, 4],
+        ];
+        let mut s = Solver::try_from((config.clone(), cls.as_ref())).expect("can't build");
+        assert!(matches!(
+            s.solve_with_assumptions(&[-5]),
+            Ok(Certificate::SAT(_))
+        ));
+        assert!(matches!(
+            s.solve_with_assumptions(&[8]),
+            Ok(Certificate::SAT(_))
+        ));
+        s.add_clause([-7, 1]).expect("can't add");
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        if let Ok(Certificate::SAT(ans)) = s.solve_with_assumptions(&[-1]) {
+            for c in cls.iter() {
+                assert!(
+                    c.iter().any(|l| ans.contains(l)),
+                    "{:?} falsifies {:?}",
+                    ans,
+                    c
+                );
+            }
+        } else {
+            panic!("failed to solve under -1");
+        }
+        // Clauses added after a search bring back the clauses blocked on their vars,
+        // and the ones blocked on the vars in the restored clauses.
+        let mut seed: usize = 1;
+        let mut rand = |n: usize| {
+            seed = (seed * 1_103_515_245 + 12_345) % 2_147_483_648;
+            seed / 65_536 % n
+        };
+        for _ in 0..200 {
+            let mut cls: Vec<Vec<i32>> = Vec::new();
+            while cls.len() < 20 {
+                let mut c: Vec<i32> = Vec::new();
+                let len = 2 + rand(2);
+                while c.len() < len {
+                    let v = rand(10) as i32 + 1;
+                    let l = if rand(2) == 0 { v } else { -v };
+                    if !c.contains(&l) && !c.contains(&-l) {
+                        c.push(l);
+                    }
+                }
+                cls.push(c);
+            }
+            let mut s = Solver::try_from((config.clone(), &cls[..12])).expect("can't build");
+            let mut answer = s.solve();
+            for n in 13..=cls.len() {
+                if !matches!(answer, Ok(Certificate::SAT(_))) || s.add_clause(&cls[n - 1]).is_err()
+                {
+                    break;
+                }
+                answer = s.solve();
+                if let Ok(Certificate::SAT(ref ans)) = answer {
+                    for c in cls[..n].iter() {
+                        assert!(
+                            c.iter().any(|l| ans.contains(l)),
+                            "{:?} falsifies {:?}",
+                            ans,
+                            c
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -18,7 +18,7 @@ where
 {
     let v = &mut asg.var(vi);
     let w = &mut elim[vi];
-    if asg.assign(vi).is_some() || w.aborted {
+    if asg.assign(vi).is_some() || w.aborted || v.is(Flag::ASSUMED) || v.is(Flag::FROZEN) {
         return Ok(());
     }
     debug_assert!(!v.is(Flag::ELIMINATED));
@@ -28,12 +28,18 @@ where
     let pos = &w.pos_occurs as *const Vec<ClauseId>;
     let neg = &w.neg_occurs as *const Vec<ClauseId>;
     unsafe {
-        if *timedout < (*pos).len() * (*neg).len()
-            || skip_var_elimination(asg, cdb, elim, &*pos, &*neg, vi)
-        {
+        if *timedout < (*pos).len() * (*neg).len() {
+            return Ok(());
+        }
+        // Under a gate definition, only the resolvents between a gate clause and a non-gate
+        // clause are needed. The others are tautologies or implied by them.
+        // Gate detection is charged by the number of the occurrences it looks through.
+        let gate = find_gate(cdb, vi, &*pos, &*neg);
+        *timedout = timedout.saturating_sub((*pos).len() + (*neg).len());
+        if skip_var_elimination(asg, cdb, elim, &*pos, &*neg, &gate, vi) {
             return Ok(());
         } else {
-            *timedout -= (*pos).len() * (*neg).len();
+            *timedout = timedout.saturating_sub((*pos).len() * (*neg).len());
         }
         #[cfg(feature = "trace_elimination")]
         println!("# eliminate_var {}", vi);
@@ -44,6 +50,11 @@ where
         // Produce clauses in cross product:
         for p in &*pos {
             for n in &*neg {
+                if !gate.is_empty() && gate.contains(p) == gate.contains(n) {
+                    continue;
+                }
+                // a resolvent is derived from its parents.
+                let hints = [cdb[*p].proof_id, cdb[*n].proof_id];
                 match merge(cdb, *p, *n, vi, &mut *vec) {
                     0 => {
                         #[cfg(feature = "trace_elimination")]
@@ -59,7 +70,7 @@ where
                             " - eliminate_var {}: found assign {} from {}{} and {}{}",
                             vi, lit, p, cdb[*p], n, cdb[*n],
                         );
-                        cdb.certificate_add(&*vec);
+                        cdb.certificate_add(&*vec, &hints);
                         asg.assign_at_root_level(lit)?;
                     }
                     2 => {
@@ -69,6 +80,7 @@ where
                                 &mut *vec,
                                 cdb[*p].is(Flag::LEARNT) && cdb[*n].is(Flag::LEARNT),
                                 true,
+                                &hints,
                             );
                             elim.add_cid_occur(asg, cid, &mut cdb[cid], true);
                         }
@@ -84,6 +96,7 @@ where
                             &mut *vec,
                             cdb[*p].is(Flag::LEARNT) && cdb[*n].is(Flag::LEARNT),
                             true,
+                            &hints,
                         );
                         elim.add_cid_occur(asg, cid, &mut cdb[cid], true);
                         #[cfg(feature = "trace_elimination")]
@@ -100,23 +113,11 @@ where
         //
         for cid in &*pos {
             debug_assert!(!asg.locked(&cdb[*cid], *cid));
-            #[cfg(feature = "incremental_solver")]
-            {
-                if !cdb[*cid].is(Flag::LEARNT) {
-                    cdb.make_permanent_immortal(*cid);
-                }
-            }
             cdb.detach(*cid);
             elim.remove_cid_occur(asg, *cid, &mut cdb[*cid]);
         }
         for cid in &*neg {
             debug_assert!(!asg.locked(&cdb[*cid], *cid));
-            #[cfg(feature = "incremental_solver")]
-            {
-                if !cdb[*cid].is(Flag::LEARNT) {
-                    cdb.make_permanent_immortal(*cid);
-                }
-            }
             cdb.detach(*cid);
             elim.remove_cid_occur(asg, *cid, &mut cdb[*cid]);
         }
@@ -133,6 +134,7 @@ fn skip_var_elimination<A, C>(
     elim: &Eliminator,
     pos: &[ClauseId],
     neg: &[ClauseId],
+    gate: &[ClauseId],
     v: VarId,
 ) -> bool
 where
@@ -149,6 +151,9 @@ where
     let mut cnt = 0;
     for c_pos in pos {
         for c_neg in neg {
+            if !gate.is_empty() && gate.contains(c_pos) == gate.contains(c_neg) {
+                continue;
+            }
             let (res, clause_size) = check_to_merge(asg, cdb, *c_pos, *c_neg, v);
             if res {
                 cnt += 1;
@@ -164,6 +169,158 @@ where
     false
 }
 
+/// return the clauses defining var `vi` as the output of a gate: an equivalence,
+/// AND/OR, XOR of up to three inputs or ITE. Return an empty vector if no gate is found.
+/// Learnt clauses aren't used as gate clauses.
+fn find_gate<C>(cdb: &C, vi: VarId, pos: &[ClauseId], neg: &[ClauseId]) -> Vec<ClauseId>
+where
+    C: ClauseDBIF,
+{
+    let x = Lit::from_assign(vi, true);
+    find_and_gate(cdb, x, pos, neg)
+        .or_else(|| find_and_gate(cdb, !x, neg, pos))
+        .or_else(|| find_xor_gate(cdb, x, pos, neg))
+        .or_else(|| find_ite_gate(cdb, x, pos, neg))
+        .unwrap_or_default()
+}
+
+/// return the clause in `occs` consisting of `lits`.
+fn find_clause<C>(cdb: &C, occs: &[ClauseId], lits: &[Lit]) -> Option<ClauseId>
+where
+    C: ClauseDBIF,
+{
+    occs.iter()
+        .find(|cid| {
+            let c = &cdb[**cid];
+            !c.is(Flag::LEARNT) && c.len() == lits.len() && lits.iter().all(|l| c.lits.contains(l))
+        })
+        .copied()
+}
+
+/// find `o = l1 & .. & ln` made of binary clauses `[-o, li]` and `[o, -l1, .., -ln]`.
+/// It is an equivalence if n is 1, and an OR gate on `-o`.
+/// `occs` and `others` are the occurrences of `o` and `-o` respectively.
+fn find_and_gate<C>(
+    cdb: &C,
+    o: Lit,
+    occs: &[ClauseId],
+    others: &[ClauseId],
+) -> Option<Vec<ClauseId>>
+where
+    C: ClauseDBIF,
+{
+    let bins: Vec<(Lit, ClauseId)> = others
+        .iter()
+        .filter(|cid| !cdb[**cid].is(Flag::LEARNT) && cdb[**cid].len() == 2)
+        .map(|cid| {
+            let c = &cdb[*cid];
+            (
+                if c.lits[0] == !o {
+                    c.lits[1]
+                } else {
+                    c.lits[0]
+                },
+                *cid,
+            )
+        })
+        .collect::<Vec<_>>();
+    if bins.is_empty() {
+        return None;
+    }
+    for cid in occs.iter() {
+        let c = &cdb[*cid];
+        if c.is(Flag::LEARNT) {
+            continue;
+        }
+        let mut gate = vec![*cid];
+        for l in c.lits.iter().filter(|l| **l != o) {
+            match bins.iter().find(|(m, _)| *m == !*l) {
+                Some((_, bid)) => gate.push(*bid),
+                None => break,
+            }
+        }
+        if gate.len() == c.len() {
+            return Some(gate);
+        }
+    }
+    None
+}
+
+/// find `x = l1 ^ .. ^ ln` (1 < n < 4) made of the 2^n clauses of its parity.
+fn find_xor_gate<C>(cdb: &C, x: Lit, pos: &[ClauseId], neg: &[ClauseId]) -> Option<Vec<ClauseId>>
+where
+    C: ClauseDBIF,
+{
+    'next_clause: for cid in pos.iter() {
+        let c = &cdb[*cid];
+        if c.is(Flag::LEARNT) || !(3..=4).contains(&c.len()) {
+            continue;
+        }
+        let mut gate = Vec::new();
+        let mut lits = c.lits.clone();
+        for mask in 0..(1usize << c.len()) {
+            if mask.count_ones() % 2 == 1 {
+                continue;
+            }
+            for (i, l) in lits.iter_mut().enumerate() {
+                *l = if mask & (1 << i) == 0 {
+                    c.lits[i]
+                } else {
+                    !c.lits[i]
+                };
+            }
+            let occs = if lits.contains(&x) { pos } else { neg };
+            match find_clause(cdb, occs, &lits) {
+                Some(did) => gate.push(did),
+                None => continue 'next_clause,
+            }
+        }
+        return Some(gate);
+    }
+    None
+}
+
+/// find `x = if c then t else e` made of `[-x, -c, t]`, `[-x, c, e]`, `[x, -c, -t]`
+/// and `[x, c, -e]`.
+fn find_ite_gate<C>(cdb: &C, x: Lit, pos: &[ClauseId], neg: &[ClauseId]) -> Option<Vec<ClauseId>>
+where
+    C: ClauseDBIF,
+{
+    let ternaries = neg
+        .iter()
+        .filter(|cid| !cdb[**cid].is(Flag::LEARNT) && cdb[**cid].len() == 3)
+        .copied()
+        .collect::<Vec<_>>();
+    for c1 in ternaries.iter() {
+        let lits1 = cdb[*c1]
+            .lits
+            .iter()
+            .filter(|l| **l != !x)
+            .copied()
+            .collect::<Vec<_>>();
+        for (a, t) in [(lits1[0], lits1[1]), (lits1[1], lits1[0])] {
+            // `a` is the negation of the condition.
+            for c2 in ternaries.iter() {
+                let lits2 = &cdb[*c2].lits;
+                if c1 == c2 || !lits2.contains(&!a) {
+                    continue;
+                }
+                let e = *lits2.iter().find(|l| **l != !x && **l != !a).unwrap();
+                if e.vi() == a.vi() {
+                    continue;
+                }
+                if let (Some(c3), Some(c4)) = (
+                    find_clause(cdb, pos, &[x, a, !t]),
+                    find_clause(cdb, pos, &[x, !a, !e]),
+                ) {
+                    return Some(vec![*c1, *c2, c3, c4]);
+                }
+            }
+        }
+    }
+    None
+}
+
 /// Returns:
 /// - `(false, -)` if one of the clauses is always satisfied.
 /// - `(true, n)` if they are merge-able to a n-literal clause.
@@ -253,19 +410,18 @@ fn make_eliminated_clauses<C>(
     C: ClauseDBIF,
 {
     let tmp = &mut elim.elim_lits;
-    if neg.len() < pos.len() {
-        for cid in neg {
-            debug_assert!(!cdb[*cid].is(Flag::DEAD));
-            make_eliminated_clause(cdb, tmp, v, *cid);
-        }
-        make_eliminating_unit_clause(tmp, Lit::from_assign(v, true));
+    // Both sides are saved in order to restore them on reactivation.
+    // `extend_model` checks the smaller side, which is saved later, first.
+    let (larger, smaller, default) = if neg.len() < pos.len() {
+        (pos, neg, true)
     } else {
-        for cid in pos {
-            debug_assert!(!cdb[*cid].is(Flag::DEAD));
-            make_eliminated_clause(cdb, tmp, v, *cid);
-        }
-        make_eliminating_unit_clause(tmp, Lit::from_assign(v, false));
+        (neg, pos, false)
+    };
+    for cid in larger.iter().chain(smaller.iter()) {
+        debug_assert!(!cdb[*cid].is(Flag::DEAD));
+        make_eliminated_clause(cdb, tmp, v, *cid);
     }
+    make_eliminating_unit_clause(tmp, Lit::from_assign(v, default));
 }
 
 fn make_eliminating_unit_clause(vec: &mut Vec<Lit>, x: Lit) {
@@ -307,6 +463,7 @@ mod tests {
     use crate::{
         assign::VarManipulateIF,
         cdb::{Clause, ClauseDB},
+        config::Config,
         processor::EliminateIF,
         solver::Solver,
     };
@@ -357,4 +514,91 @@ mod tests {
             .all(|c| !c.lits.contains(&Lit::from_assign(vi, false))
                 && !c.lits.contains(&Lit::from_assign(vi, false))));
     }
+    #[test]
+    fn test_eliminate_gate() {
+        let gate_of = |cnf: Vec<Vec<i32>>| {
+            let Solver {
+                ref mut asg,
+                ref mut cdb,
+                ref mut elim,
+                ..
+            } = Solver::try_from((Config::default(), cnf.as_ref())).expect("failed to build");
+            elim.activate();
+            elim.prepare(asg, cdb, true);
+            let mut gate = find_gate(cdb, 1, &elim[1].pos_occurs, &elim[1].neg_occurs)
+                .iter()
+                .map(|cid| cdb[*cid].as_vec())
+                .collect::<Vec<_>>();
+            gate.sort();
+            gate
+        };
+        // 1 = 2 & 3
+        assert_eq!(
+            gate_of(vec![vec![-1, 2], vec![1, 5], vec![-1, 3], vec![1, -2, -3]]),
+            vec![vec![-1, 2], vec![-1, 3], vec![1, -2, -3]]
+        );
+        // 1 = 2 ^ 3
+        assert_eq!(
+            gate_of(vec![
+                vec![-1, 2, 3],
+                vec![-1, -2, -3],
+                vec![1, -2, 3],
+                vec![1, 2, -3],
+                vec![1, 4]
+            ])
+            .len(),
+            4
+        );
+        // 1 = if 2 then 3 else 4
+        assert_eq!(
+            gate_of(vec![
+                vec![-1, -2, 3],
+                vec![-1, 2, 4],
+                vec![1, -2, -3],
+                vec![1, 2, -4],
+                vec![-1, 5, 6]
+            ])
+            .len(),
+            4
+        );
+        assert!(gate_of(vec![vec![-1, 2], vec![1, 3, 4], vec![1, -2, 5]]).is_empty());
+
+        // Only the resolvents between gate and non-gate clauses are generated.
+        let cnf = vec![
+            vec![-1, 2],
+            vec![-1, 3],
+            vec![1, -2, -3],
+            vec![1, 5],
+            vec![-1, 4, 6],
+        ];
+        let Solver {
+            ref mut asg,
+            ref mut cdb,
+            ref mut elim,
+            ref mut state,
+            ..
+        } = Solver::try_from((Config::default(), cnf.as_ref())).expect("failed to build");
+        elim.activate();
+        elim.prepare(asg, cdb, true);
+        // 2 positive and 3 negative occurrences: too many resolvents for the budget
+        let mut timedout = 5;
+        eliminate_var(asg, cdb, elim, state, 1, &mut timedout).expect("panic");
+        assert!(!asg.var(1).is(Flag::ELIMINATED));
+        assert_eq!(timedout, 5);
+        // Gate detection is charged as well as resolution.
+        let mut timedout = 10_000;
+        eliminate_var(asg, cdb, elim, state, 1, &mut timedout).expect("panic");
+        assert!(asg.var(1).is(Flag::ELIMINATED));
+        assert!(timedout <= 10_000 - (2 + 3) - 2 * 3);
+        let mut clauses = cdb
+            .as_vec()
+            .into_iter()
+            .map(|mut v| {
+                v.sort_unstable();
+                v
+            })
+            .collect::<Vec<_>>();
+        clauses.sort();
+        assert_eq!(clauses, vec![vec![-3, -2, 4, 6], vec![2, 5], vec![3, 5]]);
+    }
 }
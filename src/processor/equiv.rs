@@ -0,0 +1,304 @@
+/// Equivalent literal substitution on the binary implication graph
+use {
+    super::{EliminateIF, Eliminator},
+    crate::{assign::AssignIF, cdb::ClauseDBIF, types::*},
+    std::collections::{hash_map::Entry, HashMap},
+};
+
+/// substitute every literal in a strongly connected component of the binary implication
+/// graph with its representative, then remove the tautologies and duplicates.
+/// A frozen or assumed var is preferred as the representative, and never substituted.
+/// Each substituted var is eliminated with a pair of binary clauses defining it, which
+/// are used by `extend_model` and `reactivate`.
+/// It's skipped under LRAT since the substituted clauses have no hints.
+/// Return the number of substituted vars.
+///
+/// # Errors
+/// if solver becomes inconsistent.
+///
+/// # CAVEAT
+/// *precondition*: decision level == 0, and the eliminator isn't running.
+pub fn substitute<A, C>(
+    asg: &mut A,
+    cdb: &mut C,
+    elim: &mut Eliminator,
+) -> Result<usize, SolverError>
+where
+    A: AssignIF,
+    C: ClauseDBIF,
+{
+    debug_assert_eq!(asg.decision_level(), 0);
+    debug_assert!(!elim.is_running());
+    if cdb.needs_hints() {
+        return Ok(0);
+    }
+    let nv = asg.var_stats().0;
+    let mut map: Vec<Lit> = (0..2 * (nv + 1)).map(Lit::from).collect::<Vec<_>>();
+    let mut substituted: Vec<(Lit, Lit)> = Vec::new();
+    {
+        let mut done: Vec<bool> = vec![false; nv + 1];
+        let mut in_scc: Vec<bool> = vec![false; 2 * (nv + 1)];
+        for scc in strongly_connected_components(asg, cdb).iter() {
+            if done[scc[0].vi()] {
+                // This is the dual of a handled one.
+                continue;
+            }
+            for l in scc.iter() {
+                in_scc[usize::from(*l)] = true;
+            }
+            let contradictory = scc.iter().any(|l| in_scc[usize::from(!*l)]);
+            for l in scc.iter() {
+                in_scc[usize::from(*l)] = false;
+                done[l.vi()] = true;
+            }
+            if contradictory {
+                return Err(SolverError::Inconsistent);
+            }
+            let fixed = |l: &&Lit| {
+                let v = asg.var(l.vi());
+                v.is(Flag::ASSUMED) || v.is(Flag::FROZEN)
+            };
+            let rep = *scc
+                .iter()
+                .filter(fixed)
+                .min_by_key(|l| l.vi())
+                .or_else(|| scc.iter().min_by_key(|l| l.vi()))
+                .unwrap();
+            for l in scc.iter() {
+                if *l == rep || fixed(&l) {
+                    continue;
+                }
+                map[usize::from(*l)] = rep;
+                map[usize::from(!*l)] = !rep;
+                substituted.push((*l, rep));
+            }
+        }
+    }
+    if substituted.is_empty() {
+        return Ok(0);
+    }
+    let mut units: Vec<Lit> = Vec::new();
+    let mut vec: Vec<Lit> = Vec::new();
+    for ci in 1..cdb.len() {
+        let cid = ClauseId::from(ci);
+        let c = &cdb[cid];
+        if c.is(Flag::DEAD) || c.lits.iter().all(|l| map[usize::from(*l)] == *l) {
+            continue;
+        }
+        let learnt = c.is(Flag::LEARNT);
+        let mut tautology = false;
+        vec.clear();
+        for l in c.lits.iter() {
+            let m = map[usize::from(*l)];
+            if vec.contains(&!m) {
+                tautology = true;
+                break;
+            }
+            if !vec.contains(&m) {
+                vec.push(m);
+            }
+        }
+        cdb.detach(cid);
+        if tautology {
+            continue;
+        }
+        // Dead binary clauses are still watched until garbage collection.
+        let registered = vec.len() == 2
+            && cdb.bin_watcher_lists()[usize::from(!vec[0])]
+                .iter()
+                .any(|w| w.blocker == vec[1] && !cdb[w.c].is(Flag::DEAD));
+        // The substituted clauses are RUP by the binary clauses still alive.
+        match vec.len() {
+            1 => {
+                cdb.certificate_add(&vec, &[]);
+                units.push(vec[0]);
+            }
+            2 if registered => (),
+            _ => {
+                cdb.new_clause(asg, &mut vec, learnt, true, &[]);
+            }
+        }
+    }
+    remove_duplicates(asg, cdb);
+    for (l, rep) in substituted.iter() {
+        let vi = l.vi();
+        let x = Lit::from_assign(vi, true);
+        let r = if bool::from(*l) { *rep } else { !*rep };
+        // The first literal of each record is on the substituted var.
+        elim.elim_lits
+            .extend_from_slice(&[x, !r, Lit::from(2usize), !x, r, Lit::from(2usize)]);
+        asg.set_eliminated(vi);
+    }
+    cdb.garbage_collect();
+    for l in units.iter() {
+        asg.assign_at_root_level(*l)?;
+    }
+    if asg.propagate(cdb).is_none() {
+        Ok(substituted.len())
+    } else {
+        Err(SolverError::Inconsistent)
+    }
+}
+
+/// return the strongly connected components of more than one literal in the binary
+/// implication graph over unassigned and uneliminated vars, by Tarjan's algorithm.
+fn strongly_connected_components<A, C>(asg: &A, cdb: &C) -> Vec<Vec<Lit>>
+where
+    A: AssignIF,
+    C: ClauseDBIF,
+{
+    let len = 2 * (asg.var_stats().0 + 1);
+    let active = |l: Lit| asg.assign(l.vi()).is_none() && !asg.var(l.vi()).is(Flag::ELIMINATED);
+    // `bin[l]` holds the binary clauses propagating when `l` becomes true.
+    let bin = cdb.bin_watcher_lists();
+    let mut index: Vec<usize> = vec![0; len];
+    let mut low: Vec<usize> = vec![0; len];
+    let mut on_stack: Vec<bool> = vec![false; len];
+    let mut stack: Vec<Lit> = Vec::new();
+    let mut path: Vec<(Lit, usize)> = Vec::new();
+    let mut counter = 0;
+    let mut sccs: Vec<Vec<Lit>> = Vec::new();
+    for root in (2..len).map(Lit::from) {
+        if index[usize::from(root)] != 0 || !active(root) {
+            continue;
+        }
+        path.push((root, 0));
+        'dfs: while let Some((l, from)) = path.pop() {
+            let li = usize::from(l);
+            if from == 0 {
+                counter += 1;
+                index[li] = counter;
+                low[li] = counter;
+                stack.push(l);
+                on_stack[li] = true;
+            }
+            for (i, w) in bin[li].iter().enumerate().skip(from) {
+                let m = w.blocker;
+                if cdb[w.c].is(Flag::DEAD) || !active(m) {
+                    continue;
+                }
+                let mi = usize::from(m);
+                if index[mi] == 0 {
+                    path.push((l, i + 1));
+                    path.push((m, 0));
+                    continue 'dfs;
+                } else if on_stack[mi] {
+                    low[li] = low[li].min(index[mi]);
+                }
+            }
+            if low[li] == index[li] {
+                let mut scc = Vec::new();
+                while let Some(m) = stack.pop() {
+                    on_stack[usize::from(m)] = false;
+                    scc.push(m);
+                    if m == l {
+                        break;
+                    }
+                }
+                if 1 < scc.len() {
+                    sccs.push(scc);
+                }
+            }
+            if let Some((p, _)) = path.last() {
+                let pi = usize::from(*p);
+                low[pi] = low[pi].min(low[li]);
+            }
+        }
+    }
+    sccs
+}
+
+/// remove the duplicated clauses. A permanent clause is kept rather than a learnt.
+fn remove_duplicates<A, C>(asg: &A, cdb: &mut C)
+where
+    A: AssignIF,
+    C: ClauseDBIF,
+{
+    let mut seen: HashMap<Vec<Lit>, ClauseId> = HashMap::new();
+    for ci in 1..cdb.len() {
+        let cid = ClauseId::from(ci);
+        if cdb[cid].is(Flag::DEAD) {
+            continue;
+        }
+        let mut key = cdb[cid].lits.clone();
+        key.sort_unstable();
+        match seen.entry(key) {
+            Entry::Vacant(e) => {
+                e.insert(cid);
+            }
+            Entry::Occupied(mut e) => {
+                let other = *e.get();
+                let (keep, drop) = if cdb[other].is(Flag::LEARNT) && !cdb[cid].is(Flag::LEARNT) {
+                    (cid, other)
+                } else {
+                    (other, cid)
+                };
+                if asg.locked(&cdb[drop], drop) {
+                    continue;
+                }
+                cdb.detach(drop);
+                e.insert(keep);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assign::{AssignStack, PropagateIF, VarManipulateIF},
+        cdb::ClauseDB,
+        config::Config,
+    };
+
+    #[test]
+    fn test_substitute() {
+        let config = Config::default();
+        let cnf = CNFDescription {
+            num_of_variables: 5,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let mut cdb = ClauseDB::instantiate(&config, &cnf);
+        let mut elim = Eliminator::instantiate(&config, &cnf);
+        // 1 -> 2 -> -3 -> 1 makes 1, 2 and -3 equivalent.
+        // [1, 4, 5] and [2, 4, 5] become duplicates, and [-2, -3, 4] a tautology.
+        for c in &[
+            vec![-1, 2],
+            vec![-2, -3],
+            vec![3, 1],
+            vec![1, 4, 5],
+            vec![2, 4, 5],
+            vec![-2, -3, 4],
+            vec![-3, -4, 5],
+        ] {
+            let mut lits = c.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+            let pid = cdb.given_proof_id();
+            cdb.new_given_clause(&mut asg, &mut lits, pid);
+        }
+        assert_eq!(substitute(&mut asg, &mut cdb, &mut elim), Ok(2));
+        assert!(!asg.var(1).is(Flag::ELIMINATED));
+        assert!(asg.var(2).is(Flag::ELIMINATED));
+        assert!(asg.var(3).is(Flag::ELIMINATED));
+        let mut clauses = cdb
+            .iter()
+            .skip(1)
+            .filter(|c| !c.is(Flag::DEAD))
+            .map(|c| {
+                let mut v = c.lits.iter().map(|l| i32::from(*l)).collect::<Vec<_>>();
+                v.sort_unstable();
+                v
+            })
+            .collect::<Vec<_>>();
+        clauses.sort();
+        assert_eq!(clauses, vec![vec![-4, 1, 5], vec![1, 4, 5]]);
+        // extend a model of the rest.
+        for l in &[-1, 4, 5] {
+            asg.assign_by_decision(Lit::from(*l));
+        }
+        let model = asg.extend_model(&mut cdb, elim.eliminated_lits());
+        assert_eq!(model[2], Some(false));
+        assert_eq!(model[3], Some(true));
+    }
+}
@@ -1,5 +1,8 @@
 //!
+//! * private module `blocked` provides blocked and covered clause elimination
 //! * private module `eliminate` provides var elimination
+//! * private module `equiv` provides equivalent literal substitution
+//! * private module `probe` provides failed literal probing
 //! * private module `subsume` provides clause subsumption
 //!
 //!# Example
@@ -21,12 +24,20 @@
 //!  assert!(0 < asg.num_eliminated_vars);
 //!```
 
+mod blocked;
 mod eliminate;
+mod equiv;
 mod heap;
+mod probe;
 mod subsume;
 
+pub use self::{equiv::substitute, probe::probe};
+
 use {
-    self::{eliminate::eliminate_var, heap::VarOrderIF, subsume::try_subsume},
+    self::{
+        blocked::eliminate_blocked_clauses, eliminate::eliminate_var, heap::VarOrderIF,
+        subsume::try_subsume,
+    },
     crate::{
         assign::AssignIF,
         cdb::ClauseDBIF,
@@ -37,6 +48,7 @@ use {
     std::{
         ops::{Index, IndexMut, Range, RangeFrom},
         slice::Iter,
+        sync::atomic::Ordering,
     },
 };
 
@@ -98,6 +110,12 @@ pub trait EliminateIF {
     fn stats(&self, vi: VarId) -> Option<(usize, usize)>;
     /// return the constraints on eliminated literals.
     fn eliminated_lits(&self) -> &[Lit];
+    /// make an eliminated var alive again and return the clauses which were removed by its elimination.
+    /// The clauses witnessed by it in blocked clause elimination are returned as well.
+    /// They are taken off from the constraints on eliminated literals.
+    fn reactivate<A>(&mut self, asg: &mut A, vi: VarId) -> Vec<Vec<Lit>>
+    where
+        A: AssignIF;
 }
 
 /// API for getting stats about Eliminator's internal data.
@@ -214,6 +232,10 @@ pub struct Eliminator {
     pub eliminate_occurrence_limit: usize,
     /// Stop subsumption if the size of a clause is over this
     pub subsume_literal_limit: usize,
+    /// Remove blocked clauses
+    pub eliminate_blocked_clause: bool,
+    /// Remove covered clauses as well as blocked clauses
+    pub eliminate_covered_clause: bool,
     /// var
     var: Vec<LitOccurs>,
     num_full_elimination: usize,
@@ -235,6 +257,8 @@ impl Default for Eliminator {
             eliminate_grow_limit: 0, // 64
             eliminate_occurrence_limit: 800,
             subsume_literal_limit: 100,
+            eliminate_blocked_clause: false,
+            eliminate_covered_clause: false,
             var: Vec::new(),
             num_full_elimination: 0,
             num_sat_elimination: 0,
@@ -341,6 +365,8 @@ impl Instantiate for Eliminator {
             eliminate_var_occurrence_limit: config.elm_var_occ,
             eliminate_grow_limit: config.elm_grw_lim,
             subsume_literal_limit: config.elm_cls_lim,
+            eliminate_blocked_clause: config.use_bce(),
+            eliminate_covered_clause: config.use_cce(),
             var: LitOccurs::new(nv + 1),
             ..Eliminator::default()
         }
@@ -354,9 +380,8 @@ impl Instantiate for Eliminator {
                 self.var_queue.idxs.push(len);
                 self.var_queue.idxs[0] = len;
             }
-            SolverEvent::Reinitialize => {
-                self.elim_lits.clear();
-            }
+            // The constraints on eliminated vars are kept for their reactivation.
+            SolverEvent::Reinitialize => (),
             _ => (),
         }
     }
@@ -528,6 +553,38 @@ impl EliminateIF for Eliminator {
     fn eliminated_lits(&self) -> &[Lit] {
         &self.elim_lits
     }
+    fn reactivate<A>(&mut self, asg: &mut A, vi: VarId) -> Vec<Vec<Lit>>
+    where
+        A: AssignIF,
+    {
+        let mut clauses = Vec::new();
+        if !asg.var(vi).is(Flag::ELIMINATED) && !asg.var(vi).is(Flag::BLOCKING) {
+            return clauses;
+        }
+        // Each record is a clause or a unit whose first literal is on the eliminated var,
+        // or the witness of a blocked clause, followed by its length.
+        let mut kept = Vec::with_capacity(self.elim_lits.len());
+        let mut end = self.elim_lits.len();
+        while 0 < end {
+            let width = usize::from(self.elim_lits[end - 1]);
+            let start = end - 1 - width;
+            let record = &self.elim_lits[start..end - 1];
+            if record[0].vi() != vi {
+                kept.push((start, end));
+            } else if 1 < width {
+                clauses.push(record.to_vec());
+            }
+            end = start;
+        }
+        let mut lits = Vec::with_capacity(self.elim_lits.len());
+        for (start, end) in kept.iter().rev() {
+            lits.extend_from_slice(&self.elim_lits[*start..*end]);
+        }
+        self.elim_lits = lits;
+        asg.var_mut(vi).turn_off(Flag::BLOCKING);
+        asg.reactivate(vi);
+        clauses
+    }
 }
 
 impl Eliminator {
@@ -646,7 +703,9 @@ impl Eliminator {
             {
                 break;
             }
-            if 0.1 <= state.elapsed().unwrap_or(1.0) - start {
+            if 0.1 <= state.elapsed().unwrap_or(1.0) - start
+                || state.interrupt.load(Ordering::Relaxed)
+            {
                 self.clear_clause_queue(cdb);
                 self.clear_var_queue(asg);
                 break;
@@ -675,6 +734,9 @@ impl Eliminator {
             let nc = cdb.count() as f64;
             (6.0 * nv.log(2.0) * nc) as usize
         };
+        if self.eliminate_blocked_clause {
+            eliminate_blocked_clauses(asg, cdb, self, &mut timedout);
+        }
         while self.bwdsub_assigns < asg.stack_len()
             || !self.var_queue.is_empty()
             || !self.clause_queue.is_empty()
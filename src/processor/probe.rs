@@ -0,0 +1,268 @@
+/// Failed literal probing on the binary implication graph
+use {
+    crate::{assign::AssignIF, cdb::ClauseDBIF, solver::SolverEvent, state::State, types::*},
+    std::cmp::Reverse,
+};
+
+/// assert failed literals and the literals implied by both polarities of a var.
+/// Probes are the roots of the binary implication graph, followed by the vars having
+/// binary implications from both polarities. The work is limited by `prb_budget`
+/// propagated literals. Units are written to the certificate, while lifted literals
+/// are skipped under LRAT since they have no hints.
+/// Return the numbers of failed and lifted literals.
+///
+/// # Errors
+/// if solver becomes inconsistent.
+///
+/// # CAVEAT
+/// *precondition*: decision level == 0.
+pub fn probe<A, C>(asg: &mut A, cdb: &mut C, state: &State) -> Result<(usize, usize), SolverError>
+where
+    A: AssignIF + Instantiate,
+    C: ClauseDBIF,
+{
+    debug_assert_eq!(asg.decision_level(), 0);
+    let nv = asg.var_stats().0;
+    let mut roots: Vec<(Lit, usize)> = Vec::new();
+    let mut pairs: Vec<(Lit, usize)> = Vec::new();
+    {
+        let bin = cdb.bin_watcher_lists();
+        for vi in 1..=nv {
+            if asg.assign(vi).is_some() || asg.var(vi).is(Flag::ELIMINATED) {
+                continue;
+            }
+            let pos = Lit::from_assign(vi, true);
+            let neg = !pos;
+            // `bin[l]` holds the binary clauses propagating when `l` becomes true.
+            match (bin[usize::from(pos)].len(), bin[usize::from(neg)].len()) {
+                (0, 0) => (),
+                (n, 0) => roots.push((pos, n)),
+                (0, n) => roots.push((neg, n)),
+                (p, n) => pairs.push((pos, p + n)),
+            }
+        }
+    }
+    roots.sort_by_key(|(_, n)| Reverse(*n));
+    pairs.sort_by_key(|(_, n)| Reverse(*n));
+    // Probing must not disturb phase saving.
+    let phases = asg
+        .var_iter()
+        .map(|v| v.is(Flag::PHASE))
+        .collect::<Vec<_>>();
+    asg.handle(SolverEvent::Vivify(true));
+    let mut seen: Vec<bool> = vec![false; 2 * (nv + 1)];
+    let mut prober = Prober {
+        budget: state.config.prb_budget,
+        num_failed: 0,
+        num_lifted: 0,
+    };
+    let result = prober.run(asg, cdb, &roots, &pairs, &mut seen);
+    asg.handle(SolverEvent::Vivify(false));
+    for (v, b) in asg.var_iter_mut().zip(phases.iter()) {
+        v.set(Flag::PHASE, *b);
+    }
+    result.map(|_| (prober.num_failed, prober.num_lifted))
+}
+
+/// the state of a probing pass
+struct Prober {
+    /// the remaining number of propagated literals
+    budget: usize,
+    /// the number of asserted failed literals
+    num_failed: usize,
+    /// the number of asserted literals implied by both polarities
+    num_lifted: usize,
+}
+
+impl Prober {
+    fn run<A, C>(
+        &mut self,
+        asg: &mut A,
+        cdb: &mut C,
+        roots: &[(Lit, usize)],
+        pairs: &[(Lit, usize)],
+        seen: &mut [bool],
+    ) -> MaybeInconsistent
+    where
+        A: AssignIF,
+        C: ClauseDBIF,
+    {
+        for (p, _) in roots.iter() {
+            if self.budget == 0 {
+                return Ok(());
+            }
+            if asg.assign(p.vi()).is_none() {
+                self.probe_lit(asg, cdb, *p)?;
+            }
+        }
+        for (p, _) in pairs.iter() {
+            if self.budget == 0 {
+                return Ok(());
+            }
+            if asg.assign(p.vi()).is_some() {
+                continue;
+            }
+            let implied = match self.probe_lit(asg, cdb, *p)? {
+                Some(v) => v,
+                None => continue,
+            };
+            if asg.assign(p.vi()).is_some() {
+                continue;
+            }
+            for q in implied.iter() {
+                seen[usize::from(*q)] = true;
+            }
+            let common = self.probe_lit(asg, cdb, !*p)?.map(|v| {
+                v.into_iter()
+                    .filter(|q| seen[usize::from(*q)])
+                    .collect::<Vec<_>>()
+            });
+            for q in implied.iter() {
+                seen[usize::from(*q)] = false;
+            }
+            // Lifted literals are RUP in DRAT, but we have no hints for LRAT.
+            if cdb.needs_hints() {
+                continue;
+            }
+            for q in common.unwrap_or_default() {
+                if asg.assigned(q).is_some() {
+                    continue;
+                }
+                cdb.certificate_add(&[!*p, q], &[]);
+                cdb.certificate_add(&[q], &[]);
+                self.num_lifted += 1;
+                self.assert_at_root(asg, cdb, q)?;
+            }
+        }
+        Ok(())
+    }
+    /// probe `p` and return the implied literals if `p` is not failed.
+    /// Otherwise assert `!p`.
+    fn probe_lit<A, C>(
+        &mut self,
+        asg: &mut A,
+        cdb: &mut C,
+        p: Lit,
+    ) -> Result<Option<Vec<Lit>>, SolverError>
+    where
+        A: AssignIF,
+        C: ClauseDBIF,
+    {
+        let start = asg.stack_len();
+        asg.assign_by_decision(p);
+        let cc = asg.propagate(cdb);
+        let len = asg.stack_len();
+        self.budget = self.budget.saturating_sub(len - start);
+        if cc.is_none() {
+            let implied = asg.stack_range(start + 1..len).to_vec();
+            asg.cancel_until(0);
+            return Ok(Some(implied));
+        }
+        let hints = if cdb.needs_hints() {
+            cdb.derive_hints(asg, &[!p], cc)
+        } else {
+            Some(Vec::new())
+        };
+        asg.cancel_until(0);
+        if let Some(hints) = hints {
+            cdb.certificate_add(&[!p], &hints);
+            self.num_failed += 1;
+            self.assert_at_root(asg, cdb, !p)?;
+        }
+        Ok(None)
+    }
+    fn assert_at_root<A, C>(&mut self, asg: &mut A, cdb: &mut C, l: Lit) -> MaybeInconsistent
+    where
+        A: AssignIF,
+        C: ClauseDBIF,
+    {
+        asg.assign_at_root_level(l)?;
+        if asg.propagate(cdb).is_none() {
+            Ok(())
+        } else {
+            Err(SolverError::Inconsistent)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assign::{AssignStack, VarManipulateIF},
+        cdb::ClauseDB,
+        config::Config,
+    };
+
+    #[test]
+    fn test_probe() {
+        let config = Config::default();
+        let cnf = CNFDescription {
+            num_of_variables: 7,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let mut cdb = ClauseDB::instantiate(&config, &cnf);
+        let state = State::instantiate(&config, &cnf);
+        // 1 is a failed root: 1 -> 2, 1 -> 3, and 2 -> -3.
+        // -7 is a failed root: -7 -> -5 -> -4, -6, but 4 or 6 holds.
+        // Then -5 fails as a var having implications from both polarities.
+        for c in &[
+            [-1, 2],
+            [-1, 3],
+            [-2, -3],
+            [-4, 5],
+            [4, 6],
+            [-6, 5],
+            [-5, 7],
+        ] {
+            let mut lits = c.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+            let pid = cdb.given_proof_id();
+            cdb.new_given_clause(&mut asg, &mut lits, pid);
+        }
+        let phases = asg
+            .var_iter()
+            .map(|v| v.is(Flag::PHASE))
+            .collect::<Vec<_>>();
+        assert_eq!(probe(&mut asg, &mut cdb, &state), Ok((3, 0)));
+        assert_eq!(asg.decision_level(), 0);
+        assert_eq!(asg.assigned(Lit::from(-1)), Some(true));
+        assert_eq!(asg.assigned(Lit::from(7)), Some(true));
+        assert_eq!(asg.assigned(Lit::from(5)), Some(true));
+        assert_eq!(
+            phases,
+            asg.var_iter()
+                .map(|v| v.is(Flag::PHASE))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lift() {
+        let config = Config::default();
+        let cnf = CNFDescription {
+            num_of_variables: 6,
+            ..CNFDescription::default()
+        };
+        let mut asg = AssignStack::instantiate(&config, &cnf);
+        let mut cdb = ClauseDB::instantiate(&config, &cnf);
+        // 4 -> 5 and -4 -> 6 -> 5
+        for c in &[[-4, 5], [4, 6], [-6, 5]] {
+            let mut lits = c.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+            let pid = cdb.given_proof_id();
+            cdb.new_given_clause(&mut asg, &mut lits, pid);
+        }
+        let mut prober = Prober {
+            budget: 100,
+            num_failed: 0,
+            num_lifted: 0,
+        };
+        let mut seen: Vec<bool> = vec![false; 2 * 7];
+        assert!(prober
+            .run(&mut asg, &mut cdb, &[], &[(Lit::from(4), 2)], &mut seen)
+            .is_ok());
+        assert_eq!((prober.num_failed, prober.num_lifted), (0, 1));
+        assert_eq!(asg.assigned(Lit::from(5)), Some(true));
+        assert_eq!(asg.assigned(Lit::from(4)), None);
+    }
+}
@@ -31,7 +31,12 @@ where
         Some(l) => {
             #[cfg(feature = "trace_elimination")]
             println!("BackSubC subsumes {} from {} and {}", l, cid, did,);
-            strengthen_clause(asg, cdb, elim, did, !l)?;
+            let hint = if cid.is_lifted_lit() {
+                cdb.unit_proof_id(l)
+            } else {
+                cdb[cid].proof_id
+            };
+            strengthen_clause(asg, cdb, elim, did, !l, hint)?;
             elim.enqueue_var(asg, l.vi(), true);
         }
         None => {}
@@ -74,7 +79,7 @@ where
     Some(ret)
 }
 
-/// removes `l` from clause `cid`
+/// removes `l` from clause `cid`, which is derived with a clause `hint`
 /// - calls `enqueue_clause`
 /// - calls `enqueue_var`
 fn strengthen_clause<A, C>(
@@ -83,6 +88,7 @@ fn strengthen_clause<A, C>(
     elim: &mut Eliminator,
     cid: ClauseId,
     l: Lit,
+    hint: usize,
 ) -> MaybeInconsistent
 where
     A: AssignIF,
@@ -92,7 +98,7 @@ where
     debug_assert!(1 < cdb[cid].len());
     cdb.touch_var(l.vi());
     debug_assert!(!cid.is_none());
-    if cdb.strengthen(cid, l) {
+    if cdb.strengthen(cid, l, hint) {
         // Vaporize the binary clause
         debug_assert!(2 == cdb[cid].len());
         let c0 = cdb[cid][0];
@@ -106,7 +112,8 @@ where
 
         cdb.detach(cid);
         elim.remove_cid_occur(asg, cid, &mut cdb[cid]);
-        cdb.certificate_add(&[c0]);
+        let hints = [hint, cdb[cid].proof_id];
+        cdb.certificate_add(&[c0], &hints);
         asg.assign_at_root_level(c0)
     } else {
         #[cfg(feature = "trace_elimination")]
@@ -117,10 +124,6 @@ where
 
         elim.enqueue_clause(cid, &mut cdb[cid]);
         elim.remove_lit_occur(asg, l, cid);
-        unsafe {
-            let vec = &cdb[cid].lits[..] as *const [Lit];
-            cdb.certificate_add(&*vec);
-        }
         Ok(())
     }
 }
@@ -3,7 +3,7 @@ use {
     super::{restart::Restarter, Certificate, Solver, SolverEvent, SolverResult, State, StateIF},
     crate::{
         assign::{AssignIF, AssignStack, PropagateIF, VarManipulateIF},
-        cdb::{ClauseDB, ClauseDBIF},
+        cdb::{ClauseDB, ClauseDBIF, ProofFile, ProofSink},
         processor::{EliminateIF, Eliminator},
         types::*,
     },
@@ -94,6 +94,85 @@ pub trait SatSolverIF {
     /// assert_eq!(s.solve(), Ok(Certificate::SAT(vec![1, 2, 3, 4, 5, -6, 7, 8, -9])));
     /// ```
     fn add_var(&mut self) -> usize;
+    /// freeze a var to protect it from elimination.
+    /// An eliminated var is reactivated before freezing.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::Inconsistent` if the reactivation makes the solver inconsistent.
+    /// * `SolverError::OutOfRange` if it is out of range for var index.
+    ///
+    /// # Example
+    /// ```
+    /// use crate::splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from("tests/uf8.cnf").expect("can't load");
+    /// assert!(s.freeze(1).is_ok());
+    /// assert!(s.freeze(2).is_ok());
+    /// assert!(matches!(s.freeze(9), Err(SolverError::OutOfRange)));
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// assert!(matches!(s.solve_with_assumptions(&[-1, 2]), Ok(Certificate::SAT(_))));
+    /// assert!(s.melt(2).is_ok());
+    /// ```
+    fn freeze(&mut self, vi: VarId) -> Result<&mut Solver, SolverError>;
+    /// melt a frozen var to allow its elimination again.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::OutOfRange` if it is out of range for var index.
+    fn melt(&mut self, vi: VarId) -> Result<&mut Solver, SolverError>;
+    /// make an eliminated var alive again by restoring the clauses which were removed by its
+    /// elimination. The vars eliminated in them are reactivated as well.
+    /// `add_clause`, `add_assignment`, `freeze` and `solve_with_assumptions` call this
+    /// for the vars they refer to.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::Inconsistent` if a restored clause conflicts with existing assignments.
+    /// * `SolverError::OutOfRange` if it is out of range for var index.
+    ///
+    /// # Example
+    /// ```
+    /// use crate::splr::*;
+    /// use crate::splr::assign::VarManipulateIF;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from("tests/uf20-01.cnf").expect("can't load");
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// for vi in 1..=20 {
+    ///     assert!(s.reactivate(vi).is_ok());
+    /// }
+    /// assert_eq!(s.asg.num_eliminated_vars, 0);
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn reactivate(&mut self, vi: VarId) -> Result<&mut Solver, SolverError>;
+    /// open a constraint scope and return the number of open scopes.
+    /// The clauses and assignments added until the corresponding `pop` are guarded by
+    /// the activation literal of the scope, which is assumed in every invocation of `solve`.
+    /// Note: an activation literal takes a new var, which doesn't appear in models.
+    ///
+    /// # Example
+    /// ```
+    /// use crate::splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from((Config::default(), vec![vec![1, 2], vec![-1, 2]].as_ref()))
+    ///     .expect("can't build");
+    /// assert_eq!(s.push(), 1);
+    /// assert!(s.add_clause(vec![-2]).is_ok());
+    /// assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+    /// assert!(s.pop().is_ok());
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn push(&mut self) -> usize;
+    /// close the last constraint scope. The clauses added in it and the learnt clauses derived
+    /// from them are disabled permanently, and removed.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::OutOfRange` if no scope is open.
+    fn pop(&mut self) -> Result<&mut Solver, SolverError>;
     /// make a solver and load a CNF into it.
     ///
     /// # Errors
@@ -174,6 +253,10 @@ impl SatSolverIF for Solver {
         if val == 0 || self.asg.num_vars < val.abs() as usize {
             return Err(SolverError::OutOfRange);
         }
+        if !self.state.scopes.is_empty() {
+            return self.add_clause([val]);
+        }
+        self.reactivate(val.unsigned_abs() as usize)?;
         self.asg.assign_at_root_level(Lit::from(val)).map(|_| self)
     }
     fn add_clause<V>(&mut self, vec: V) -> Result<&mut Solver, SolverError>
@@ -185,11 +268,17 @@ impl SatSolverIF for Solver {
                 return Err(SolverError::OutOfRange);
             }
         }
+        for i in vec.as_ref().iter() {
+            self.reactivate(i.unsigned_abs() as usize)?;
+        }
         let mut clause = vec
             .as_ref()
             .iter()
             .map(|i| Lit::from(*i))
             .collect::<Vec<Lit>>();
+        if let Some(act) = self.state.scopes.last() {
+            clause.push(!*act);
+        }
         if self.add_unchecked_clause(&mut clause).is_none() {
             return Err(SolverError::Inconsistent);
         }
@@ -209,6 +298,81 @@ impl SatSolverIF for Solver {
         state.handle(SolverEvent::NewVar);
         asg.num_vars
     }
+    fn freeze(&mut self, vi: VarId) -> Result<&mut Solver, SolverError> {
+        self.reactivate(vi)?;
+        self.asg.var_mut(vi).turn_on(Flag::FROZEN);
+        Ok(self)
+    }
+    fn melt(&mut self, vi: VarId) -> Result<&mut Solver, SolverError> {
+        if vi == 0 || self.asg.num_vars < vi {
+            return Err(SolverError::OutOfRange);
+        }
+        self.asg.var_mut(vi).turn_off(Flag::FROZEN);
+        Ok(self)
+    }
+    fn reactivate(&mut self, vi: VarId) -> Result<&mut Solver, SolverError> {
+        if vi == 0 || self.asg.num_vars < vi {
+            return Err(SolverError::OutOfRange);
+        }
+        // The vars in restored clauses come back before the clauses are added,
+        // as well as the witnesses of blocked clauses on them.
+        let mut queue = vec![vi];
+        let mut restored = Vec::new();
+        let Solver {
+            ref mut asg,
+            ref mut elim,
+            ..
+        } = self;
+        while let Some(vi) = queue.pop() {
+            for clause in elim.reactivate(asg, vi) {
+                queue.extend(clause.iter().map(|l| l.vi()).filter(|vi| {
+                    asg.var(*vi).is(Flag::ELIMINATED) || asg.var(*vi).is(Flag::BLOCKING)
+                }));
+                restored.push(clause);
+            }
+        }
+        for mut clause in restored {
+            if self.add_unchecked_clause(&mut clause).is_none() {
+                return Err(SolverError::Inconsistent);
+            }
+        }
+        Ok(self)
+    }
+    fn push(&mut self) -> usize {
+        let vi = self.add_var();
+        self.asg.var_mut(vi).turn_on(Flag::FROZEN);
+        self.state.scopes.push(Lit::from_assign(vi, true));
+        self.state.scope_vars.push(vi);
+        self.state.scopes.len()
+    }
+    fn pop(&mut self) -> Result<&mut Solver, SolverError> {
+        let act = self.state.scopes.pop().ok_or(SolverError::OutOfRange)?;
+        self.asg.var_mut(act.vi()).turn_off(Flag::FROZEN);
+        // All clauses guarded by `act`, including learnts derived from them, are satisfied now.
+        if self.add_unchecked_clause(&mut vec![!act]).is_none() {
+            return Err(SolverError::Inconsistent);
+        }
+        let Solver {
+            ref mut asg,
+            ref mut cdb,
+            ..
+        } = self;
+        if !asg.propagate(cdb).is_none() {
+            return Err(SolverError::Inconsistent);
+        }
+        let guarded = cdb
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, c)| !c.is(Flag::DEAD) && c.lits.contains(&!act))
+            .map(|(i, _)| ClauseId::from(i))
+            .collect::<Vec<_>>();
+        for cid in guarded {
+            cdb.detach(cid);
+        }
+        cdb.garbage_collect();
+        Ok(self)
+    }
     /// # Examples
     ///
     /// ```
@@ -236,12 +400,6 @@ impl SatSolverIF for Solver {
         elim.handle(SolverEvent::Reinitialize);
         rst.handle(SolverEvent::Reinitialize);
         state.handle(SolverEvent::Reinitialize);
-
-        let mut tmp = Vec::new();
-        std::mem::swap(&mut tmp, &mut cdb.eliminated_permanent);
-        while let Some(mut vec) = tmp.pop() {
-            cdb.new_clause(asg, &mut vec, false, false);
-        }
     }
 }
 
@@ -260,9 +418,14 @@ impl Solver {
             return None;
         }
         debug_assert!(asg.decision_level() == 0);
-        if lits.iter().any(|l| asg.assigned(*l).is_some()) {
-            cdb.certificate_add(lits);
-        }
+        let given_id = cdb.given_proof_id();
+        // The given clause is replaced with a simplified one. So it should be deleted from
+        // the certification after its replacement is added.
+        let original = if lits.iter().any(|l| asg.assigned(*l).is_some()) {
+            Some(lits.clone())
+        } else {
+            None
+        };
         lits.sort_unstable();
         let mut j = 0;
         let mut l_ = NULL_LIT; // last literal; [x, x.negate()] means tautology.
@@ -270,6 +433,9 @@ impl Solver {
             let li = lits[i];
             let sat = asg.assigned(li);
             if sat == Some(true) || !li == l_ {
+                if let Some(ref o) = original {
+                    cdb.certificate_delete(given_id, o);
+                }
                 return Some(ClauseId::default());
             } else if sat != Some(false) && li != l_ {
                 lits[j] = li;
@@ -278,17 +444,60 @@ impl Solver {
             }
         }
         lits.truncate(j);
-        match lits.len() {
+        // The simplified clause is derived from the given one and the units of removed literals.
+        let hints = match original {
+            Some(ref o) if cdb.needs_hints() => o
+                .iter()
+                .filter(|l| asg.assigned(**l) == Some(false))
+                .map(|l| cdb.unit_proof_id(!*l))
+                .chain(std::iter::once(given_id))
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        let result = match lits.len() {
             0 => None, // Empty clause is UNSAT.
-            1 => asg
-                .assign_at_root_level(lits[0])
-                .map_or(None, |_| Some(ClauseId::default())),
+            1 => {
+                if original.is_some() {
+                    cdb.certificate_add(lits, &hints);
+                } else {
+                    cdb.set_unit_proof_id(lits[0], given_id);
+                }
+                asg.assign_at_root_level(lits[0])
+                    .map_or(None, |_| Some(ClauseId::default()))
+            }
             _ => {
-                let cid = cdb.new_clause(asg, lits, false, false);
+                let cid = if original.is_some() {
+                    cdb.new_clause(asg, lits, false, false, &hints)
+                } else {
+                    cdb.new_given_clause(asg, lits, given_id)
+                };
                 elim.add_cid_occur(asg, cid, &mut cdb[cid], true);
                 Some(cid)
             }
+        };
+        if let (Some(o), Some(_)) = (original, result) {
+            cdb.certificate_delete(given_id, &o);
+        }
+        result
+    }
+    /// make a proof file if certification is required, and start tracing an UNSAT core if required.
+    fn open_proof(&mut self) -> MaybeInconsistent {
+        let config = &self.state.config;
+        if config.use_certification {
+            let path = config.io_odir.join(&config.io_pfile);
+            let proof = if config.use_lrat {
+                ProofFile::create_lrat(&path)?
+            } else if config.use_binary_proof {
+                ProofFile::create_binary(&path)?
+            } else {
+                ProofFile::create(&path, &self.state.target.pathname)?
+            };
+            self.cdb.certified = ProofSink::from(proof);
+        }
+        if config.use_core {
+            self.cdb.certified.trace_core();
         }
+        Ok(())
     }
     #[cfg(not(feature = "no_IO"))]
     fn inject(mut self, mut reader: BufReader<File>) -> Result<Solver, SolverError> {
@@ -296,6 +505,7 @@ impl Solver {
         self.state
             .progress(&self.asg, &self.cdb, &self.elim, &self.rst);
         self.state.flush("Initialization phase: loading...");
+        self.open_proof()?;
         let mut buf = String::new();
         loop {
             buf.clear();
@@ -338,6 +548,7 @@ impl Solver {
         self.state
             .progress(&self.asg, &self.cdb, &self.elim, &self.rst);
         self.state.flush("injecting...");
+        self.open_proof()?;
         for ints in v.iter() {
             for i in ints.as_ref().iter() {
                 if *i == 0 || self.asg.num_vars < i.abs() as usize {
@@ -0,0 +1,576 @@
+//! Crate `check` implements a backward DRAT proof checker.
+use {
+    crate::{
+        assign::{AssignIF, AssignReason, AssignStack, PropagateIF, VarManipulateIF},
+        cdb::{CertifiedRecord, ClauseDB, ClauseDBIF},
+        config::Config,
+        types::*,
+    },
+    std::{
+        collections::HashMap,
+        convert::TryFrom,
+        fmt,
+        fs::File,
+        io::{BufRead, Read},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Reasons why a proof is rejected.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofError {
+    /// failed to read a file.
+    IOError,
+    /// an illegal token or variable at the line (or the byte offset in a binary proof).
+    ParseError(usize),
+    /// the empty clause can't be derived.
+    NoRefutation,
+    /// the lemma at the step is neither RUP nor RAT.
+    NotRedundant(usize, Vec<i32>),
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProofError::IOError => write!(f, "an IO error"),
+            ProofError::ParseError(n) => write!(f, "a parse error at {}", n),
+            ProofError::NoRefutation => write!(f, "no refutation"),
+            ProofError::NotRedundant(n, v) => {
+                write!(f, "a non-redundant lemma {:?} at step {}", v, n)
+            }
+        }
+    }
+}
+
+/// Where a clause is held.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Slot {
+    /// not in the current formula
+    Inactive,
+    /// a tautology, which is always satisfied
+    Tautology,
+    /// the empty clause
+    Empty,
+    /// a unit clause, which `ClauseDB` can't hold
+    Unit,
+    /// a clause in `ClauseDB`
+    Stored(ClauseId),
+}
+
+/// A backward DRAT checker built on the watch lists and propagation of splr.
+/// Lemmas are checked in the reverse order, and only the ones used to derive
+/// the empty clause are checked.
+#[derive(Debug)]
+pub struct ProofChecker {
+    asg: AssignStack,
+    cdb: ClauseDB,
+    /// literals of the given clauses and lemmas in the original order
+    clause: Vec<Vec<Lit>>,
+    slot: Vec<Slot>,
+    /// `true` if the clause is used to derive the empty clause
+    core: Vec<bool>,
+    /// the list of additions and deletions of clauses, in the index of `clause`
+    proof: Vec<(CertifiedRecord, usize)>,
+    /// the index of the clause at a `ClauseId`
+    owner: Vec<usize>,
+    /// the indices of live unit clauses
+    units: Vec<usize>,
+    /// map from sorted literals to the indices of live clauses
+    live: HashMap<Vec<Lit>, Vec<usize>>,
+    /// the index + 1 of the unit clause asserting a var
+    unit_reason: Vec<usize>,
+    seen: Vec<bool>,
+    /// the number of the given clauses
+    num_given: usize,
+    /// `true` if the current formula has the empty clause
+    refuted: bool,
+    /// `true` if `ClauseDB` has dead clauses
+    dirty: bool,
+}
+
+impl Instantiate for ProofChecker {
+    fn instantiate(config: &Config, cnf: &CNFDescription) -> ProofChecker {
+        let nv = cnf.num_of_variables;
+        ProofChecker {
+            asg: AssignStack::instantiate(config, cnf),
+            cdb: ClauseDB::instantiate(config, cnf),
+            clause: Vec::new(),
+            slot: Vec::new(),
+            core: Vec::new(),
+            proof: Vec::new(),
+            owner: Vec::new(),
+            units: Vec::new(),
+            live: HashMap::new(),
+            unit_reason: vec![0; nv + 1],
+            seen: vec![false; nv + 1],
+            num_given: 0,
+            refuted: false,
+            dirty: false,
+        }
+    }
+}
+
+impl TryFrom<&PathBuf> for ProofChecker {
+    type Error = ProofError;
+    /// make a checker holding the CNF in a file.
+    fn try_from(path: &PathBuf) -> Result<Self, Self::Error> {
+        let CNFReader { cnf, reader } =
+            CNFReader::try_from(path).map_err(|_| ProofError::IOError)?;
+        let mut checker = ProofChecker::instantiate(&Config::default(), &cnf);
+        parse_text(reader, cnf.num_of_variables, |_, v| checker.add_clause(v))?;
+        Ok(checker)
+    }
+}
+
+impl ProofChecker {
+    /// add a clause of the formula.
+    pub fn add_clause(&mut self, vec: &[i32]) {
+        debug_assert!(self.proof.is_empty());
+        let i = self.push(vec);
+        self.activate(i);
+        self.num_given += 1;
+    }
+    /// add a lemma. Lemmas after the empty clause are ignored.
+    pub fn add_lemma(&mut self, vec: &[i32]) {
+        if self.refuted {
+            return;
+        }
+        let i = self.push(vec);
+        self.activate(i);
+        self.proof.push((CertifiedRecord::ADD, i));
+    }
+    /// delete a clause. A deletion of a missing clause is ignored.
+    pub fn delete_lemma(&mut self, vec: &[i32]) {
+        if self.refuted {
+            return;
+        }
+        let mut key = vec.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+        key.sort_unstable();
+        key.dedup();
+        if let Some(i) = self.live.get_mut(&key).and_then(|v| v.pop()) {
+            self.deactivate(i);
+            self.proof.push((CertifiedRecord::DELETE, i));
+        }
+    }
+    /// load a proof file in the textual or binary DRAT format.
+    ///
+    /// # Errors
+    ///
+    /// * `ProofError::IOError` if it failed to read the file.
+    /// * `ProofError::ParseError` if the file contains an illegal token.
+    pub fn inject_proof(&mut self, path: &Path) -> Result<(), ProofError> {
+        let mut bytes = Vec::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut bytes))
+            .map_err(|_| ProofError::IOError)?;
+        let nv = self.seen.len() - 1;
+        // Every record in a binary proof ends with a null byte, which never occurs in a text.
+        if bytes.contains(&0) {
+            parse_binary(&bytes, nv, |r, v| self.inject_record(r, v))
+        } else {
+            parse_text(&bytes[..], nv, |r, v| self.inject_record(r, v))
+        }
+    }
+    /// check the loaded proof backward and return the number of checked lemmas.
+    ///
+    /// # Errors
+    ///
+    /// * `ProofError::NoRefutation` if the proof doesn't derive the empty clause.
+    /// * `ProofError::NotRedundant` if a lemma used in the refutation can't be verified.
+    pub fn check(&mut self) -> Result<usize, ProofError> {
+        let mut num_checked = 0;
+        if !self.refuted && !self.rup(&[]) {
+            return Err(ProofError::NoRefutation);
+        }
+        for step in (0..self.proof.len()).rev() {
+            let (record, i) = self.proof[step];
+            if record == CertifiedRecord::DELETE {
+                self.activate(i);
+                continue;
+            }
+            self.deactivate(i);
+            if self.clause[i].is_empty() {
+                self.core[i] = true;
+            }
+            if !self.core[i] {
+                continue;
+            }
+            let lits = self.clause[i].clone();
+            if !self.rup(&lits) && !self.rat(&lits) {
+                return Err(ProofError::NotRedundant(
+                    step + 1,
+                    lits.iter().map(|l| i32::from(*l)).collect(),
+                ));
+            }
+            num_checked += 1;
+        }
+        Ok(num_checked)
+    }
+    /// return the number of lemmas used in the refutation.
+    pub fn num_core_lemmas(&self) -> usize {
+        self.core[self.num_given..].iter().filter(|b| **b).count()
+    }
+    fn inject_record(&mut self, record: CertifiedRecord, vec: &[i32]) {
+        match record {
+            CertifiedRecord::ADD => self.add_lemma(vec),
+            CertifiedRecord::DELETE => self.delete_lemma(vec),
+        }
+    }
+    /// register a clause without activating it.
+    fn push(&mut self, vec: &[i32]) -> usize {
+        let mut lits: Vec<Lit> = Vec::with_capacity(vec.len());
+        let mut slot = Slot::Inactive;
+        for l in vec.iter().map(|i| Lit::from(*i)) {
+            if lits.contains(&!l) {
+                slot = Slot::Tautology;
+            }
+            if !lits.contains(&l) {
+                lits.push(l);
+            }
+        }
+        self.clause.push(lits);
+        self.slot.push(slot);
+        self.core.push(false);
+        self.clause.len() - 1
+    }
+    /// put a clause into the current formula.
+    fn activate(&mut self, i: usize) {
+        if self.slot[i] == Slot::Tautology {
+            return;
+        }
+        let mut key = self.clause[i].clone();
+        key.sort_unstable();
+        self.live.entry(key).or_default().push(i);
+        self.slot[i] = match self.clause[i].len() {
+            0 => {
+                self.refuted = true;
+                Slot::Empty
+            }
+            1 => {
+                self.units.push(i);
+                Slot::Unit
+            }
+            _ => {
+                let mut lits = self.clause[i].clone();
+                let cid = self
+                    .cdb
+                    .new_clause(&mut self.asg, &mut lits, false, false, &[]);
+                let k = cid.ordinal as usize;
+                if self.owner.len() <= k {
+                    self.owner.resize(k + 1, 0);
+                }
+                self.owner[k] = i;
+                Slot::Stored(cid)
+            }
+        };
+    }
+    /// remove a clause from the current formula.
+    fn deactivate(&mut self, i: usize) {
+        match self.slot[i] {
+            Slot::Inactive | Slot::Tautology => return,
+            Slot::Empty => self.slot[i] = Slot::Inactive,
+            Slot::Unit => {
+                if let Some(k) = self.units.iter().position(|j| *j == i) {
+                    self.units.swap_remove(k);
+                }
+                self.slot[i] = Slot::Inactive;
+            }
+            Slot::Stored(cid) => {
+                self.cdb.detach(cid);
+                self.dirty = true;
+                self.slot[i] = Slot::Inactive;
+            }
+        }
+        let mut key = self.clause[i].clone();
+        key.sort_unstable();
+        if let Some(v) = self.live.get_mut(&key) {
+            if let Some(k) = v.iter().position(|j| *j == i) {
+                v.swap_remove(k);
+            }
+        }
+    }
+    /// return `true` if the negation of `lits` leads to a conflict by unit propagation.
+    /// Clauses used in the conflict are marked as core.
+    fn rup(&mut self, lits: &[Lit]) -> bool {
+        debug_assert_eq!(self.asg.decision_level(), 0);
+        if self.dirty {
+            self.cdb.garbage_collect();
+            self.dirty = false;
+        }
+        for l in lits {
+            if self.asg.assigned(*l).is_none() {
+                self.asg.assign_by_decision(!*l);
+            }
+        }
+        let mut conflict = None;
+        for i in self.units.iter() {
+            let l = self.clause[*i][0];
+            match self.asg.assigned(l) {
+                Some(true) => (),
+                Some(false) => {
+                    conflict = Some(*i);
+                    break;
+                }
+                None => {
+                    self.asg.assign_by_decision(l);
+                    self.unit_reason[l.vi()] = *i + 1;
+                }
+            }
+        }
+        if conflict.is_none() {
+            let cc = self.asg.propagate(&mut self.cdb);
+            if cc != ClauseId::default() {
+                conflict = Some(self.owner[cc.ordinal as usize]);
+            }
+        }
+        if let Some(i) = conflict {
+            self.mark_core(i);
+        }
+        for l in self.asg.stack_iter() {
+            self.unit_reason[l.vi()] = 0;
+        }
+        self.asg.cancel_until(0);
+        conflict.is_some()
+    }
+    /// return `true` if `lits` is a resolution asymmetric tautology on its first literal.
+    fn rat(&mut self, lits: &[Lit]) -> bool {
+        let pivot = match lits.first() {
+            Some(l) => !*l,
+            None => return false,
+        };
+        let candidates = (0..self.clause.len())
+            .filter(|i| {
+                matches!(self.slot[*i], Slot::Unit | Slot::Stored(_))
+                    && self.clause[*i].contains(&pivot)
+            })
+            .collect::<Vec<_>>();
+        for i in candidates.iter() {
+            let mut resolvent = lits.to_vec();
+            let mut tautology = false;
+            for l in self.clause[*i].iter().filter(|l| **l != pivot) {
+                if resolvent.contains(&!*l) {
+                    tautology = true;
+                    break;
+                }
+                if !resolvent.contains(l) {
+                    resolvent.push(*l);
+                }
+            }
+            if !tautology && !self.rup(&resolvent) {
+                return false;
+            }
+        }
+        for i in candidates.iter() {
+            self.core[*i] = true;
+        }
+        true
+    }
+    /// mark the clauses on the implication graph of a conflicting clause.
+    fn mark_core(&mut self, i: usize) {
+        self.core[i] = true;
+        for l in self.clause[i].iter() {
+            self.seen[l.vi()] = true;
+        }
+        for k in (0..self.asg.stack_len()).rev() {
+            let vi = self.asg.stack(k).vi();
+            if !self.seen[vi] {
+                continue;
+            }
+            self.seen[vi] = false;
+            if 0 < self.unit_reason[vi] {
+                self.core[self.unit_reason[vi] - 1] = true;
+            } else if let AssignReason::Implication(cid, _) = self.asg.reason(vi) {
+                let j = self.owner[cid.ordinal as usize];
+                self.core[j] = true;
+                for l in self.clause[j].iter() {
+                    if l.vi() != vi {
+                        self.seen[l.vi()] = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// parse a DIMACS-like text and pass each clause to `f`.
+fn parse_text<R, F>(reader: R, nv: usize, mut f: F) -> Result<(), ProofError>
+where
+    R: BufRead,
+    F: FnMut(CertifiedRecord, &[i32]),
+{
+    let mut record = CertifiedRecord::ADD;
+    let mut vec: Vec<i32> = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|_| ProofError::IOError)?;
+        if line.starts_with('c') || line.starts_with('p') {
+            continue;
+        }
+        for token in line.split_whitespace() {
+            if token == "d" && vec.is_empty() {
+                record = CertifiedRecord::DELETE;
+                continue;
+            }
+            match token.parse::<i32>() {
+                Ok(0) => {
+                    f(record, &vec);
+                    record = CertifiedRecord::ADD;
+                    vec.clear();
+                }
+                Ok(i) if (i.unsigned_abs() as usize) <= nv => vec.push(i),
+                _ => return Err(ProofError::ParseError(n + 1)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// parse a binary DRAT proof and pass each clause to `f`.
+fn parse_binary<F>(bytes: &[u8], nv: usize, mut f: F) -> Result<(), ProofError>
+where
+    F: FnMut(CertifiedRecord, &[i32]),
+{
+    let mut vec: Vec<i32> = Vec::new();
+    let mut iter = bytes.iter().enumerate();
+    while let Some((n, b)) = iter.next() {
+        let record = match b {
+            b'a' => CertifiedRecord::ADD,
+            b'd' => CertifiedRecord::DELETE,
+            _ => return Err(ProofError::ParseError(n)),
+        };
+        loop {
+            let mut u: u64 = 0;
+            let mut shift = 0;
+            loop {
+                let (n, b) = iter.next().ok_or(ProofError::ParseError(bytes.len()))?;
+                if 56 < shift {
+                    return Err(ProofError::ParseError(n));
+                }
+                u |= ((b & 0x7f) as u64) << shift;
+                shift += 7;
+                if b & 0x80 == 0 {
+                    break;
+                }
+            }
+            if u == 0 {
+                break;
+            }
+            let vi = (u >> 1) as usize;
+            if vi == 0 || nv < vi {
+                return Err(ProofError::ParseError(n));
+            }
+            vec.push(if u & 1 == 0 { vi as i32 } else { -(vi as i32) });
+        }
+        f(record, &vec);
+        vec.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cdb::{ProofBuffer, ProofFile},
+        solver::{Certificate, SatSolverIF, SolveIF, Solver},
+    };
+
+    fn checker(nv: usize, cnf: &[&[i32]]) -> ProofChecker {
+        let mut checker = ProofChecker::instantiate(
+            &Config::default(),
+            &CNFDescription {
+                num_of_variables: nv,
+                ..CNFDescription::default()
+            },
+        );
+        for c in cnf {
+            checker.add_clause(c);
+        }
+        checker
+    }
+
+    #[test]
+    fn test_rup_and_rat() {
+        let lits = |v: &[i32]| v.iter().map(|i| Lit::from(*i)).collect::<Vec<_>>();
+        let mut c = checker(4, &[&[1, 2], &[-1, 3]]);
+        assert!(!c.rup(&lits(&[4])));
+        assert!(c.rat(&lits(&[4])));
+        assert!(!c.rup(&lits(&[-3])));
+        assert!(!c.rat(&lits(&[-3])));
+        assert!(c.rup(&lits(&[2, 3])));
+        assert!(!c.rat(&lits(&[-1])));
+    }
+
+    #[test]
+    fn test_check() {
+        let cnf: &[&[i32]] = &[&[1, 2], &[1, -2], &[-1, 3], &[-1, -3]];
+        let mut c = checker(3, cnf);
+        c.add_lemma(&[1]);
+        c.delete_lemma(&[1, 2]);
+        c.add_lemma(&[]);
+        assert_eq!(c.check(), Ok(2));
+        assert_eq!(c.num_core_lemmas(), 2);
+        let mut c = checker(3, cnf);
+        c.delete_lemma(&[-3, -1]);
+        c.add_lemma(&[]);
+        assert_eq!(c.check(), Err(ProofError::NotRedundant(2, vec![])));
+        let mut c = checker(3, &cnf[1..]);
+        assert_eq!(c.check(), Err(ProofError::NoRefutation));
+    }
+
+    #[test]
+    fn test_solver_proof() {
+        let cnf = PathBuf::from("tests/unsat.cnf");
+        for binary in [false, true].iter() {
+            let path = std::env::temp_dir().join(format!(
+                "splr-check-{}-{}.drat",
+                std::process::id(),
+                binary
+            ));
+            let mut config = Config::from("tests/unsat.cnf");
+            config.use_certification = true;
+            let mut s = Solver::build(&config).expect("failed to load");
+            let sink = if *binary {
+                ProofFile::create_binary(&path)
+            } else {
+                ProofFile::create(&path, "tests/unsat.cnf")
+            };
+            s.cdb.certified = sink.expect("can't create").into();
+            assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+            assert!(s.cdb.certified.flush().is_ok());
+            let mut c = ProofChecker::try_from(&cnf).expect("failed to load");
+            assert_eq!(c.inject_proof(&path), Ok(()));
+            std::fs::remove_file(&path).expect("can't remove");
+            assert!(c.check().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_proof_with_imports() {
+        // the pigeonhole problem: 7 pigeons and 6 holes
+        let var = |p: i32, h: i32| p * 6 + h + 1;
+        let mut cnf = (0..7)
+            .map(|p| (0..6).map(|h| var(p, h)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for h in 0..6 {
+            for p in 0..7 {
+                for q in p + 1..7 {
+                    cnf.push(vec![-var(p, h), -var(q, h)]);
+                }
+            }
+        }
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        let proof = ProofBuffer::default();
+        s.cdb.certified = proof.clone().into();
+        // The import survives elimination, but it's refused because the proof can't derive it.
+        assert!(s.freeze(var(0, 0) as usize).is_ok());
+        s.import_handle().lock().unwrap().push(vec![-var(0, 0)]);
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        assert!(s.import_handle().lock().unwrap().is_empty());
+        let cls = cnf.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        let mut c = checker(42, &cls);
+        for (record, vec) in proof.records().iter() {
+            c.inject_record(*record, vec);
+        }
+        assert!(c.check().is_ok());
+    }
+}
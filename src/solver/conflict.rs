@@ -6,14 +6,35 @@ use {
         State,
     },
     crate::{
-        assign::{AssignIF, AssignStack, PropagateIF, VarManipulateIF, VarRewardIF},
+        assign::{
+            AssignIF, AssignStack, ExternalPropagateIF, PropagateIF, VarManipulateIF, VarRewardIF,
+        },
         cdb::{ClauseDB, ClauseDBIF, WatchDBIF},
         processor::{EliminateIF, Eliminator},
         solver::SolverEvent,
+        state::{Hook, LearntCallback},
         types::*,
     },
 };
 
+/// pass a learnt clause to the callback if it's short enough or its LBD is small enough.
+/// A clause on the var of an activation literal holds only in its scope, so it isn't passed.
+fn export_learnt(
+    (max_len, max_lbd): (usize, usize),
+    hook: &Hook<LearntCallback>,
+    scope_vars: &[VarId],
+    lits: &[Lit],
+    lbd: usize,
+) {
+    if (lits.len() <= max_len || lbd <= max_lbd)
+        && lits
+            .iter()
+            .all(|l| scope_vars.binary_search(&l.vi()).is_err())
+    {
+        hook.call(|f| f(&lits.iter().map(|l| i32::from(*l)).collect::<Vec<_>>()));
+    }
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub fn handle_conflict(
     asg: &mut AssignStack,
@@ -24,6 +45,8 @@ pub fn handle_conflict(
     ci: ClauseId,
 ) -> MaybeInconsistent {
     let original_dl = asg.decision_level();
+    // Conflict analysis needs the reasons of literals implied by the external propagator.
+    asg.explain_external(cdb);
     // we need a catch here for handling the possibility of level zero conflict
     // at higher level due to the incoherence between the current level and conflicting
     // level in chronoBT. This leads to UNSAT solution. No need to update misc stats.
@@ -155,16 +178,7 @@ pub fn handle_conflict(
     asg.handle(SolverEvent::Conflict);
     // backtrack level by analyze
 
-    let bl_a = {
-        #[cfg(not(feature = "progress_MLD"))]
-        {
-            conflict_analyze(asg, cdb, state, ci).max(asg.root_level)
-        }
-        #[cfg(feature = "progress_MLD")]
-        {
-            conflict_analyze(asg, cdb, rst, state, ci).max(asg.root_level)
-        }
-    };
+    let bl_a = conflict_analyze(asg, cdb, rst, state, ci).max(asg.root_level);
 
     if state.new_learnt.is_empty() {
         #[cfg(debug)]
@@ -179,6 +193,13 @@ pub fn handle_conflict(
 
         return Err(SolverError::NullLearnt);
     }
+    // The antecedents of the learnt clause must be collected before backjumping.
+    let hints = if cdb.needs_hints() {
+        cdb.derive_hints(asg, &state.new_learnt, ci)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
     // asg.bump_vars(asg, cdb, ci);
     let new_learnt = &mut state.new_learnt;
     let l0 = new_learnt[0];
@@ -215,7 +236,14 @@ pub fn handle_conflict(
         //## A NEW ASSERTION by UNIT LEARNT CLAUSE GENERATION
         //
         // dump to certified even if it's a literal.
-        cdb.certificate_add(new_learnt);
+        cdb.certificate_add(new_learnt, &hints);
+        export_learnt(
+            (state.learnt_hook_len, state.learnt_hook_lbd),
+            &state.learnt_hook,
+            &state.scope_vars,
+            new_learnt,
+            1,
+        );
         if use_chronobt {
             asg.cancel_until(bl);
             debug_assert!(asg.stack_iter().all(|l| l.vi() != l0.vi()));
@@ -255,7 +283,14 @@ pub fn handle_conflict(
         } else {
             NULL_LIT
         };
-        let cid = cdb.new_clause(asg, new_learnt, true, true);
+        let cid = cdb.new_clause(asg, new_learnt, true, true, &hints);
+        export_learnt(
+            (state.learnt_hook_len, state.learnt_hook_lbd),
+            &state.learnt_hook,
+            &state.scope_vars,
+            &cdb[cid].lits,
+            cdb[cid].rank as usize,
+        );
         elim.add_cid_occur(asg, cid, &mut cdb[cid], true);
         state.c_lvl.update(cl as f64);
         state.b_lvl.update(bl as f64);
@@ -270,11 +305,7 @@ pub fn handle_conflict(
                 act = a;
             }
         }
-
-        #[cfg(feature = "progress_ACC")]
-        {
-            rst.update(ProgressUpdate::ACC(act));
-        }
+        rst.update(ProgressUpdate::ACC(act));
 
         elim.to_simplify += 1.0 / (learnt_len - 1) as f64;
         if lbd <= 20 {
@@ -297,9 +328,7 @@ pub fn handle_conflict(
 fn conflict_analyze(
     asg: &mut AssignStack,
     cdb: &mut ClauseDB,
-
-    #[cfg(feature = "progress_MLD")] rst: &mut Restarter,
-
+    rst: &mut Restarter,
     state: &mut State,
     conflicting_clause: ClauseId,
 ) -> DecisionLevel {
@@ -314,8 +343,6 @@ fn conflict_analyze(
     println!("- analyze conflicting literal {}", p);
 
     let mut path_cnt = 0;
-
-    #[cfg(feature = "progress_MLD")]
     let mut largest_clause: u16 = 2;
 
     let vi = p.vi();
@@ -371,10 +398,7 @@ fn conflict_analyze(
                 if !c.is(Flag::LEARNT) {
                     state.derive20.push(cid);
                 }
-                #[cfg(feature = "progress_MLD")]
-                {
-                    largest_clause = largest_clause.max(c.rank);
-                }
+                largest_clause = largest_clause.max(c.rank);
 
                 #[cfg(feature = "boundary_check")]
                 assert!(
@@ -496,11 +520,7 @@ fn conflict_analyze(
     debug_assert!(learnt.iter().all(|l| *l != !p));
     debug_assert_eq!(asg.level(p.vi()), dl);
     learnt[0] = !p;
-
-    #[cfg(feature = "progress_MLD")]
-    {
-        rst.update(ProgressUpdate::MLD(largest_clause));
-    }
+    rst.update(ProgressUpdate::MLD(largest_clause));
 
     #[cfg(feature = "trace_analysis")]
     println!("- appending {}, the result is {:?}", learnt[0], learnt);
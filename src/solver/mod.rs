@@ -1,8 +1,12 @@
 /// Crate `solver` provides the top-level API as a SAT solver.
 /// API to instantiate
 mod build;
+/// Crate `check` implements a DRAT proof checker.
+mod check;
 /// Crate 'conflict' handles conflicts.
 mod conflict;
+/// Crate `mus` implements a minimal unsatisfiable subset extractor.
+mod mus;
 /// Crate `restart` provides restart heuristics.
 mod restart;
 /// CDCL search engine
@@ -14,8 +18,10 @@ mod vivify;
 
 pub use self::{
     build::SatSolverIF,
+    check::{ProofChecker, ProofError},
+    mus::MusExtractor,
     restart::{RestartIF, RestartMode, Restarter, RestarterEMAs},
-    search::SolveIF,
+    search::{Budget, SolveIF},
     validate::ValidateIF,
 };
 
@@ -31,12 +37,15 @@ pub enum Certificate {
     SAT(Vec<i32>),
     /// It is unsatisfiable.
     UNSAT,
+    /// It is unknown because a budget or the time limit was exhausted, or it was interrupted.
+    UNKNOWN,
 }
 
 /// The return type of `Solver::solve`.
-/// This captures the following three cases:
+/// This captures the following four cases:
 /// * `Certificate::SAT` -- solved with a satisfiable assignment set,
-/// * `Certificate::UNSAT` -- proved that it's an unsatisfiable problem, and
+/// * `Certificate::UNSAT` -- proved that it's an unsatisfiable problem,
+/// * `Certificate::UNKNOWN` -- stopped by a budget, the time limit or an interrupt, and
 /// * `SolverException::*` -- caused by a bug
 pub type SolverResult = Result<Certificate, SolverError>;
 
@@ -166,8 +175,16 @@ impl<'a> Iterator for SolverIter<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::assign::VarManipulateIF;
-    use std::convert::{From, TryFrom};
+    use crate::{
+        assign::{ExternalPropagator, VarManipulateIF},
+        cdb::{CertifiedRecord, ClauseDBIF, ProofBuffer},
+    };
+    use std::{
+        collections::HashMap,
+        convert::{From, TryFrom},
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
 
     #[cfg_attr(not(feature = "no_IO"), test)]
     fn test_solver() {
@@ -179,6 +196,709 @@ mod tests {
         }
     }
 
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_solve_with_assumptions() {
+        let mut s = Solver::try_from("tests/uf20-01.cnf").expect("can't load");
+        let mut seed: usize = 20;
+        for _ in 0..40 {
+            let mut assumptions: Vec<i32> = Vec::new();
+            for _ in 0..4 {
+                seed = (seed * 1_103_515_245 + 12_345) % 2_147_483_648;
+                let v = (seed / 65_536 % 20) as i32 + 1;
+                assumptions.push(if seed % 7 < 3 { -v } else { v });
+            }
+            let mut fresh = Solver::try_from("tests/uf20-01.cnf").expect("can't load");
+            let mut consistent = true;
+            for a in assumptions.iter() {
+                if fresh.add_clause(vec![*a]).is_err() {
+                    consistent = false;
+                }
+            }
+            let expected = if consistent {
+                fresh.solve()
+            } else {
+                Ok(Certificate::UNSAT)
+            };
+            match s.solve_with_assumptions(&assumptions) {
+                Ok(Certificate::SAT(ans)) => {
+                    assert!(matches!(expected, Ok(Certificate::SAT(_))));
+                    for a in assumptions.iter() {
+                        assert!(ans.contains(a));
+                    }
+                }
+                Ok(Certificate::UNSAT) => {
+                    assert_eq!(expected, Ok(Certificate::UNSAT));
+                    let failed = s.failed_assumptions();
+                    assert!(!failed.is_empty());
+                    assert!(failed.iter().all(|a| assumptions.contains(a)));
+                    assert_eq!(s.solve_with_assumptions(&failed), Ok(Certificate::UNSAT));
+                }
+                e => panic!("{:?}", e),
+            }
+        }
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        // A pure literal isn't fixed in a search, which may be followed by another one.
+        let cnf = vec![vec![1, 2], vec![1, 3], vec![2, 3, 4]];
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        match s.solve_with_assumptions(&[-1]) {
+            Ok(Certificate::SAT(ans)) => assert!([-1, 2, 3].iter().all(|l| ans.contains(l))),
+            e => panic!("{:?}", e),
+        }
+        // A refutation without assumptions holds in the following invocations.
+        let cnf = vec![
+            vec![1, 2],
+            vec![-1, 2],
+            vec![1, -2],
+            vec![-1, -2],
+            vec![-3, 4],
+        ];
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        for assumptions in [vec![], vec![-4], vec![], vec![4]].iter() {
+            assert_eq!(
+                s.solve_with_assumptions(assumptions),
+                Ok(Certificate::UNSAT),
+                "{:?}",
+                assumptions
+            );
+            assert!(s.failed_assumptions().is_empty());
+        }
+        s.add_clause([3, 4]).expect("can't add");
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+    }
+
+    #[test]
+    fn test_budget() {
+        // the pigeonhole problem: 8 pigeons and 7 holes
+        let var = |p: i32, h: i32| p * 7 + h + 1;
+        let mut cnf = (0..8)
+            .map(|p| (0..7).map(|h| var(p, h)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for h in 0..7 {
+            for p in 0..8 {
+                for q in p + 1..8 {
+                    cnf.push(vec![-var(p, h), -var(q, h)]);
+                }
+            }
+        }
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        for n in [1, 10, 100].iter() {
+            let start = s.asg.num_conflict;
+            s.set_budget(Budget {
+                conflicts: Some(*n),
+                ..Budget::default()
+            });
+            assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+            assert_eq!(s.asg.num_conflict, start + n);
+        }
+        let start = s.state[Stat::Decision];
+        s.set_budget(Budget {
+            decisions: Some(20),
+            ..Budget::default()
+        });
+        assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+        assert_eq!(s.state[Stat::Decision], start + 20);
+        s.set_budget(Budget {
+            propagations: Some(0),
+            ..Budget::default()
+        });
+        assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+        s.set_budget(Budget {
+            time: Some(std::time::Duration::from_secs(0)),
+            ..Budget::default()
+        });
+        assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+        // The budget is cleared.
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        // The time limit stops it as well.
+        let mut config = Config::default();
+        config.c_tout = 0.0;
+        let mut s = Solver::try_from((config, cnf.as_ref())).expect("can't build");
+        s.state.reflection_interval = 10;
+        assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+    }
+
+    #[test]
+    fn test_interrupt() {
+        // the pigeonhole problem: 10 pigeons and 9 holes, which takes much longer than the timer
+        let var = |p: i32, h: i32| p * 9 + h + 1;
+        let mut cnf = (0..10)
+            .map(|p| (0..9).map(|h| var(p, h)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for h in 0..9 {
+            for p in 0..10 {
+                for q in p + 1..10 {
+                    cnf.push(vec![-var(p, h), -var(q, h)]);
+                }
+            }
+        }
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        let handle = s.interrupt_handle();
+        let timer = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            handle.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+        assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+        timer.join().expect("the timer panicked");
+        // The handle is reset, and the solver is still usable.
+        let homeless = (0..9).map(|h| -var(0, h)).collect::<Vec<_>>();
+        assert_eq!(s.solve_with_assumptions(&homeless), Ok(Certificate::UNSAT));
+        assert!(!s.failed_assumptions().is_empty());
+    }
+
+    #[test]
+    fn test_restart_policies() {
+        // the pigeonhole problem: 7 pigeons and 6 holes
+        let var = |p: i32, h: i32| p * 6 + h + 1;
+        let mut cnf = (0..7)
+            .map(|p| (0..6).map(|h| var(p, h)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for h in 0..6 {
+            for p in 0..7 {
+                for q in p + 1..7 {
+                    cnf.push(vec![-var(p, h), -var(q, h)]);
+                }
+            }
+        }
+        for mode in &[
+            RestartMode::Dynamic,
+            RestartMode::Luby,
+            RestartMode::Geometric,
+            RestartMode::Stabilize,
+        ] {
+            let mut config = Config::default();
+            config.rst_mode = *mode;
+            let mut s = Solver::try_from((config, cnf.as_ref())).expect("can't build");
+            assert_eq!(s.solve(), Ok(Certificate::UNSAT), "{}", mode);
+            assert!(0 < s.asg.exports().2, "no restart by {}", mode);
+        }
+        let mut config = Config::default();
+        config.inject_from(&["--LBY", "1"]);
+        assert_eq!(config.rst_mode, RestartMode::Luby);
+        for args in &[["--ACC", "1"], ["--LBK", "1"]] {
+            let mut config = Config::default();
+            config.inject_from(args);
+            let mut s = Solver::try_from((config, cnf.as_ref())).expect("can't build");
+            assert_eq!(s.solve(), Ok(Certificate::UNSAT), "{:?}", args);
+            assert!(0 < s.asg.exports().2, "no restart by {:?}", args);
+        }
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_local_search_model() {
+        // Every rephasing after the first conflict runs local search.
+        let mut config = Config::from("tests/uf250-02.cnf");
+        config.inject_from(&["--SLS", "1", "--rpc", "W", "--rpi", "1"]);
+        let mut s = Solver::build(&config).expect("can't load");
+        let cnf = s
+            .cdb
+            .iter()
+            .skip(1)
+            .filter(|c| !c.is(Flag::DEAD) && !c.is(Flag::LEARNT))
+            .map(|c| c.lits.iter().map(|l| i32::from(*l)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        if let Ok(Certificate::SAT(ans)) = s.solve() {
+            assert!(cnf.iter().all(|c| c.iter().any(|l| ans.contains(l))));
+        } else {
+            panic!("failed to solve tests/uf250-02.cnf");
+        }
+    }
+
+    #[test]
+    fn test_learnt_sharing() {
+        // the pigeonhole problem: 7 pigeons and 6 holes
+        let var = |p: i32, h: i32| p * 6 + h + 1;
+        let mut cnf = (0..7)
+            .map(|p| (0..6).map(|h| var(p, h)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for h in 0..6 {
+            for p in 0..7 {
+                for q in p + 1..7 {
+                    cnf.push(vec![-var(p, h), -var(q, h)]);
+                }
+            }
+        }
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        let mut t = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        let inbox = t.import_handle();
+        s.set_learnt_callback(
+            8,
+            2,
+            Some(Box::new(move |c: &[i32]| {
+                inbox.lock().unwrap().push(c.to_vec());
+            })),
+        );
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        let imports = t.import_handle();
+        assert!(!imports.lock().unwrap().is_empty());
+        assert_eq!(t.solve(), Ok(Certificate::UNSAT));
+        assert!(imports.lock().unwrap().is_empty());
+        // Learnt clauses in a scope aren't exported, since they depend on it.
+        let mut u = Solver::try_from((Config::default(), &cnf[..7])).expect("can't build");
+        u.push();
+        let act = u.state.scope_vars[0] as i32;
+        let exports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&exports);
+        u.set_learnt_callback(
+            usize::MAX,
+            usize::MAX,
+            Some(Box::new(move |c: &[i32]| {
+                sink.lock().unwrap().push(c.to_vec());
+            })),
+        );
+        for c in cnf[7..].iter() {
+            u.add_clause(c).expect("can't add");
+        }
+        assert_eq!(u.solve(), Ok(Certificate::UNSAT));
+        assert!(exports
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|c| !c.contains(&act) && !c.contains(&-act)));
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_freeze() {
+        let mut s = Solver::try_from("tests/uf100-010.cnf").expect("can't load");
+        assert!(s.elim.enable);
+        for vi in 1..=10 {
+            s.freeze(vi).expect("can't freeze");
+        }
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        assert!(0 < s.asg.num_eliminated_vars);
+        assert!((1..=10).all(|vi| !s.asg.var(vi).is(Flag::ELIMINATED)));
+        // Frozen vars can be assumed in later invocations.
+        for a in [vec![1, -2, 3], vec![-4, 5], vec![6, -7, 8, -9, 10]].iter() {
+            if let Ok(Certificate::SAT(ans)) = s.solve_with_assumptions(a) {
+                assert!(a.iter().all(|l| ans.contains(l)));
+            }
+        }
+        assert!(s.melt(1).is_ok());
+        assert!(!s.asg.var(1).is(Flag::FROZEN));
+    }
+
+    /// a theory: at most one of `vars` is true.
+    struct AtMostOne {
+        vars: Vec<i32>,
+        trues: Vec<i32>,
+        /// the number of `trues` at the beginning of each decision level
+        levels: Vec<usize>,
+    }
+
+    impl ExternalPropagator for AtMostOne {
+        fn notify_assignment(&mut self, lits: &[i32]) {
+            let AtMostOne { vars, trues, .. } = self;
+            trues.extend(lits.iter().filter(|l| vars.contains(l)).copied());
+        }
+        fn notify_new_decision_level(&mut self) {
+            self.levels.push(self.trues.len());
+        }
+        fn notify_backtrack(&mut self, level: usize) {
+            if level < self.levels.len() {
+                self.trues.truncate(self.levels[level]);
+                self.levels.truncate(level);
+            }
+        }
+        fn propagate(&mut self) -> Vec<i32> {
+            match self.trues.first() {
+                Some(t) => self.vars.iter().filter(|v| *v != t).map(|v| -v).collect(),
+                None => Vec::new(),
+            }
+        }
+        fn reason(&mut self, lit: i32) -> Vec<i32> {
+            vec![lit, -self.trues[0]]
+        }
+        fn check_model(&mut self, model: &[i32]) -> Vec<Vec<i32>> {
+            let trues = model
+                .iter()
+                .filter(|l| self.vars.contains(l))
+                .collect::<Vec<_>>();
+            if 1 < trues.len() {
+                vec![vec![-trues[0], -trues[1]]]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    /// a theory which rejects every model by the given clauses
+    struct Reject(Vec<Vec<i32>>);
+
+    impl ExternalPropagator for Reject {
+        fn reason(&mut self, lit: i32) -> Vec<i32> {
+            vec![lit]
+        }
+        fn check_model(&mut self, _model: &[i32]) -> Vec<Vec<i32>> {
+            self.0.clone()
+        }
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_external_propagator() {
+        let cnf = vec![vec![1, 2], vec![3, 4], vec![-1, 5]];
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        for v in [1, 2, 3].iter() {
+            s.freeze(*v).expect("can't freeze");
+        }
+        s.connect_propagator(Some(Box::new(AtMostOne {
+            vars: vec![1, 2, 3],
+            trues: Vec::new(),
+            levels: Vec::new(),
+        })))
+        .expect("can't connect");
+        match s.solve() {
+            Ok(Certificate::SAT(ans)) => {
+                assert_eq!(ans.iter().filter(|l| [1, 2, 3].contains(*l)).count(), 1);
+                assert!(ans.contains(&4));
+            }
+            e => panic!("not SAT: {:?}", e),
+        }
+        // Two of 1 to 4 are true.
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        for v in [1, 2, 3, 4].iter() {
+            s.freeze(*v).expect("can't freeze");
+        }
+        s.connect_propagator(Some(Box::new(AtMostOne {
+            vars: vec![1, 2, 3, 4],
+            trues: Vec::new(),
+            levels: Vec::new(),
+        })))
+        .expect("can't connect");
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        // The theory agrees with its encoding by clauses.
+        for k in 2..=20 {
+            let vars = (1..=20).filter(|v| v % k == 1).collect::<Vec<i32>>();
+            let mut s = Solver::try_from("tests/uf20-01.cnf").expect("can't load");
+            for v in vars.iter() {
+                s.freeze(*v as usize).expect("can't freeze");
+            }
+            s.connect_propagator(Some(Box::new(AtMostOne {
+                vars: vars.clone(),
+                trues: Vec::new(),
+                levels: Vec::new(),
+            })))
+            .expect("can't connect");
+            let mut t = Solver::try_from("tests/uf20-01.cnf").expect("can't load");
+            for (i, v) in vars.iter().enumerate() {
+                for w in vars[i + 1..].iter() {
+                    t.add_clause([-v, -w]).expect("can't add");
+                }
+            }
+            match (s.solve(), t.solve()) {
+                (Ok(Certificate::SAT(ans)), Ok(Certificate::SAT(_))) => {
+                    assert!(ans.iter().filter(|l| vars.contains(l)).count() <= 1);
+                }
+                (Ok(Certificate::UNSAT), Ok(Certificate::UNSAT)) => (),
+                e => panic!("disagree on {:?}: {:?}", vars, e),
+            }
+        }
+        // A rejection by an unknown var can't be added, so it stops the search.
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        s.connect_propagator(Some(Box::new(Reject(vec![vec![9]]))))
+            .expect("can't connect");
+        assert_eq!(s.solve(), Err(SolverError::OutOfRange));
+        // Theory clauses can't be certified.
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        s.cdb.certified = ProofBuffer::default().into();
+        assert!(matches!(
+            s.connect_propagator(Some(Box::new(Reject(Vec::new())))),
+            Err(SolverError::ProofUnavailable)
+        ));
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_push_pop() {
+        let mut s = Solver::try_from("tests/uf100-010.cnf").expect("can't load");
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        assert!(matches!(s.pop(), Err(SolverError::OutOfRange)));
+        assert_eq!(s.push(), 1);
+        s.add_assignment(1).expect("can't add");
+        assert_eq!(s.push(), 2);
+        let act = s.state.scopes[1];
+        // These make it unsatisfiable with some conflicts.
+        for i in 2..40 {
+            s.add_clause(vec![-1, i]).expect("can't add");
+            s.add_clause(vec![-i, -(i + 40)]).expect("can't add");
+            s.add_clause(vec![i + 40, -(i + 1)]).expect("can't add");
+        }
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        assert_eq!(s.failed_assumptions(), vec![]);
+        assert!(s.solve_with_assumptions(&[5]).is_ok());
+        assert!(s.pop().is_ok());
+        assert!(s
+            .cdb
+            .iter()
+            .skip(1)
+            .all(|c| c.is(Flag::DEAD) || !c.lits.contains(&!act)));
+        match s.solve() {
+            Ok(Certificate::SAT(ans)) => {
+                assert!(ans.contains(&1));
+                assert_eq!(ans.len(), 100);
+            }
+            e => panic!("{:?}", e),
+        }
+        assert!(s.pop().is_ok());
+        assert!(matches!(
+            s.solve_with_assumptions(&[-1]),
+            Ok(Certificate::SAT(_))
+        ));
+        // A scope can contradict a pure literal of the previous search.
+        let cnf = vec![vec![1, 2], vec![1, 3], vec![2, 3, 4]];
+        let mut s = Solver::try_from((Config::default(), cnf.as_ref())).expect("can't build");
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        s.push();
+        s.add_clause([-1]).expect("can't add");
+        match s.solve() {
+            Ok(Certificate::SAT(ans)) => {
+                assert_eq!(ans.len(), 4);
+                assert!(ans.contains(&-1) && ans.contains(&2) && ans.contains(&3));
+            }
+            e => panic!("{:?}", e),
+        }
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_reactivate() {
+        let cnf = "tests/uf100-010.cnf";
+        let mut s = Solver::try_from(cnf).expect("can't load");
+        assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+        let eliminated = s
+            .asg
+            .var_iter()
+            .filter(|v| v.is(Flag::ELIMINATED))
+            .map(|v| v.index as i32)
+            .collect::<Vec<_>>();
+        assert!(!eliminated.is_empty());
+        // Eliminated vars are assumed or used in new clauses as if they had been alive.
+        for (i, vi) in eliminated.iter().enumerate() {
+            let lit = if i % 2 == 0 { *vi } else { -*vi };
+            let mut fresh = Solver::try_from(cnf).expect("can't load");
+            let expected = fresh.solve_with_assumptions(&[lit]);
+            match s.solve_with_assumptions(&[lit]) {
+                Ok(Certificate::SAT(ans)) => {
+                    assert!(matches!(expected, Ok(Certificate::SAT(_))));
+                    assert!(ans.contains(&lit));
+                }
+                answer => assert_eq!(answer, expected),
+            }
+            assert!(!s.asg.var(lit.unsigned_abs() as usize).is(Flag::ELIMINATED));
+        }
+        let clause = eliminated.iter().map(|vi| -*vi).collect::<Vec<_>>();
+        let mut fresh = Solver::try_from(cnf).expect("can't load");
+        fresh.add_clause(&clause).expect("can't add");
+        let expected = matches!(fresh.solve(), Ok(Certificate::SAT(_)));
+        s.add_clause(&clause).expect("can't add");
+        match s.solve() {
+            Ok(Certificate::SAT(ans)) => {
+                assert!(expected);
+                assert!(clause.iter().any(|l| ans.contains(l)));
+                let mut validator = Solver::try_from(cnf).expect("can't load");
+                validator.inject_assignment(&ans).expect("can't inject");
+                assert_eq!(validator.validate(), None);
+            }
+            answer => assert_eq!(answer, Ok(Certificate::UNSAT)),
+        }
+    }
+
+    /// return `true` if `lemma` is derived by unit propagation on `clauses`.
+    fn is_rup(clauses: &HashMap<Vec<i32>, usize>, lemma: &[i32]) -> bool {
+        let mut assign: HashMap<i32, bool> = HashMap::new();
+        for l in lemma.iter() {
+            if assign.insert(l.abs(), *l < 0) == Some(0 < *l) {
+                return true;
+            }
+        }
+        loop {
+            let mut updated = false;
+            'next_clause: for c in clauses.keys() {
+                let mut unassigned = None;
+                for l in c.iter() {
+                    match assign.get(&l.abs()) {
+                        Some(b) if *b == (0 < *l) => continue 'next_clause,
+                        Some(_) => (),
+                        None if unassigned.is_none() => unassigned = Some(*l),
+                        None => continue 'next_clause,
+                    }
+                }
+                match unassigned {
+                    None => return true,
+                    Some(l) => {
+                        assign.insert(l.abs(), 0 < l);
+                        updated = true;
+                    }
+                }
+            }
+            if !updated {
+                return false;
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_certificate_deletions() {
+        let mut config = Config::from("tests/unsat.cnf");
+        config.use_certification = true;
+        config.quiet_mode = true;
+        config.io_odir = std::env::temp_dir();
+        config.io_pfile = PathBuf::from(format!("splr-unsat-{}.drat", std::process::id()));
+        let mut s = Solver::build(&config).expect("can't load");
+        let parse = |line: &str| {
+            let mut c = line
+                .split_whitespace()
+                .map(|i| i.parse::<i32>().expect("wrong literal"))
+                .filter(|i| *i != 0)
+                .collect::<Vec<i32>>();
+            c.sort_unstable();
+            c
+        };
+        let mut clauses: HashMap<Vec<i32>, usize> = HashMap::new();
+        let cnf = std::fs::read_to_string("tests/unsat.cnf").expect("can't load");
+        for line in cnf
+            .lines()
+            .filter(|l| !l.starts_with('c') && !l.starts_with('p'))
+        {
+            *clauses.entry(parse(line)).or_insert(0) += 1;
+        }
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        let proof_file = config.io_odir.join(&config.io_pfile);
+        let proof = std::fs::read_to_string(&proof_file).expect("can't load the proof");
+        std::fs::remove_file(&proof_file).expect("can't remove the proof");
+        let mut num_deletion = 0;
+        for line in proof.lines().filter(|l| !l.starts_with('c')) {
+            let (record, c) = match line.strip_prefix("d ") {
+                Some(l) => (CertifiedRecord::DELETE, parse(l)),
+                None => (CertifiedRecord::ADD, parse(line)),
+            };
+            match record {
+                CertifiedRecord::ADD => {
+                    assert!(is_rup(&clauses, &c), "a non-RUP lemma {:?}", c);
+                    *clauses.entry(c).or_insert(0) += 1;
+                }
+                CertifiedRecord::DELETE => {
+                    num_deletion += 1;
+                    let n = clauses.get_mut(&c).expect("deleted a non-existing clause");
+                    *n -= 1;
+                    if *n == 0 {
+                        clauses.remove(&c);
+                    }
+                }
+            }
+        }
+        assert!(0 < num_deletion);
+        assert!(is_rup(&clauses, &[]));
+        let mut stored: HashMap<Vec<i32>, usize> = HashMap::new();
+        for c in s.cdb.iter().skip(1).filter(|c| !c.lits.is_empty()) {
+            let mut v = c.lits.iter().map(|l| i32::from(*l)).collect::<Vec<i32>>();
+            v.sort_unstable();
+            *stored.entry(v).or_insert(0) += 1;
+        }
+        // every clause alive in the proof should be alive in the database, and vice versa.
+        clauses.retain(|c, _| 1 < c.len());
+        assert_eq!(clauses, stored);
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_lrat_certificate() {
+        let mut config = Config::from("tests/unsat.cnf");
+        config.use_certification = true;
+        config.use_lrat = true;
+        config.quiet_mode = true;
+        config.io_odir = std::env::temp_dir();
+        config.io_pfile = PathBuf::from(format!("splr-unsat-{}.lrat", std::process::id()));
+        let mut s = Solver::build(&config).expect("can't load");
+        let parse = |line: &str| {
+            line.split_whitespace()
+                .map(|i| i.parse::<i64>().expect("wrong number"))
+                .collect::<Vec<i64>>()
+        };
+        let mut clauses: HashMap<i64, Vec<i64>> = HashMap::new();
+        let cnf = std::fs::read_to_string("tests/unsat.cnf").expect("can't load");
+        for line in cnf
+            .lines()
+            .filter(|l| !l.starts_with('c') && !l.starts_with('p'))
+        {
+            let id = clauses.len() as i64 + 1;
+            clauses.insert(id, parse(line).into_iter().filter(|i| *i != 0).collect());
+        }
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        let proof_file = config.io_odir.join(&config.io_pfile);
+        let proof = std::fs::read_to_string(&proof_file).expect("can't load the proof");
+        std::fs::remove_file(&proof_file).expect("can't remove the proof");
+        let mut refuted = false;
+        for line in proof.lines() {
+            let v = parse(line.replace(" d ", " 0 ").as_str());
+            if line.contains(" d ") {
+                for id in v[2..].iter().filter(|i| **i != 0) {
+                    assert!(
+                        clauses.remove(id).is_some(),
+                        "deleted a non-existing clause"
+                    );
+                }
+                continue;
+            }
+            let id = v[0];
+            let n = v[1..].iter().position(|i| *i == 0).expect("no terminator") + 1;
+            let lemma = v[1..n].to_vec();
+            // every hint should be a unit or conflicting clause in order.
+            let mut assign: HashMap<i64, bool> = HashMap::new();
+            for l in lemma.iter() {
+                assign.insert(l.abs(), *l < 0);
+            }
+            let mut conflict = false;
+            for h in v[n + 1..].iter().filter(|i| **i != 0) {
+                assert!(!conflict, "{}: redundant hint {}", id, h);
+                let c = clauses.get(h).expect("a hint to a non-existing clause");
+                let mut unassigned = Vec::new();
+                for l in c.iter() {
+                    match assign.get(&l.abs()) {
+                        Some(b) => assert_ne!(*b, 0 < *l, "{}: satisfied hint {}", id, h),
+                        None => unassigned.push(*l),
+                    }
+                }
+                match unassigned.len() {
+                    0 => conflict = true,
+                    1 => {
+                        assign.insert(unassigned[0].abs(), 0 < unassigned[0]);
+                    }
+                    _ => panic!("{}: non-unit hint {}", id, h),
+                }
+            }
+            assert!(conflict, "{}: a non-RUP lemma {:?}", id, lemma);
+            refuted |= lemma.is_empty();
+            assert!(clauses.insert(id, lemma).is_none(), "a reused id {}", id);
+        }
+        assert!(refuted);
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_unsat_core() {
+        let mut config = Config::from("tests/unsat.cnf");
+        config.use_core = true;
+        config.quiet_mode = true;
+        let mut s = Solver::build(&config).expect("can't load");
+        assert_eq!(s.unsat_core(), None);
+        assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+        let core = s.unsat_core().expect("no core");
+        let cnf = std::fs::read_to_string("tests/unsat.cnf").expect("can't load");
+        let clauses = cnf
+            .lines()
+            .filter(|l| !l.starts_with('c') && !l.starts_with('p'))
+            .map(|l| {
+                l.split_whitespace()
+                    .map(|i| i.parse::<i32>().expect("wrong number"))
+                    .filter(|i| *i != 0)
+                    .collect::<Vec<i32>>()
+            })
+            .collect::<Vec<_>>();
+        assert!(!core.is_empty() && core.len() < clauses.len());
+        let subset = core.iter().map(|i| clauses[*i].clone()).collect::<Vec<_>>();
+        let result = Solver::try_from((Config::default(), subset.as_ref())).map(|mut s| s.solve());
+        assert!(matches!(
+            result,
+            Ok(Ok(Certificate::UNSAT)) | Err(Ok(Certificate::UNSAT))
+        ));
+    }
+
     macro_rules! run {
         ($vec: expr) => {
             println!(
@@ -0,0 +1,352 @@
+//! Crate `mus` implements a minimal unsatisfiable subset extractor.
+use {
+    super::{Certificate, SatSolverIF, SolveIF, Solver},
+    crate::{config::Config, types::*},
+    std::{
+        convert::TryFrom,
+        fs::File,
+        io::{BufRead, BufReader},
+        path::PathBuf,
+    },
+};
+
+/// The state of a group in extraction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum GroupState {
+    /// not determined yet
+    Unknown,
+    /// in the MUS
+    Necessary,
+    /// out of the MUS
+    Removed,
+}
+
+/// A (group-)MUS extractor based on deletion with clause-set refinement and model rotation.
+/// Each group of clauses is guarded by a selector var, which is assumed to enable the group.
+/// Group zero holds hard clauses, which are always enabled.
+#[derive(Debug)]
+pub struct MusExtractor {
+    solver: Solver,
+    /// the number of vars in the problem
+    num_vars: usize,
+    /// the group and the literals of each clause
+    clause: Vec<(usize, Vec<i32>)>,
+    /// the clauses in each group
+    group: Vec<Vec<usize>>,
+    /// the clauses containing each literal
+    occurs: Vec<Vec<usize>>,
+    state: Vec<GroupState>,
+    /// `true` if the hard clauses are inconsistent by themselves
+    inconsistent: bool,
+    /// the number of invocations of the solver
+    pub num_solve: usize,
+    /// the number of groups found by model rotation
+    pub num_rotated: usize,
+}
+
+impl TryFrom<&PathBuf> for MusExtractor {
+    type Error = SolverError;
+    /// make an extractor from a CNF or a GCNF file.
+    /// Every clause in a CNF file is a group by itself.
+    fn try_from(path: &PathBuf) -> Result<Self, Self::Error> {
+        let reader = BufReader::new(File::open(path).map_err(|_| SolverError::IOError)?);
+        let mut num_vars = 0;
+        let mut gcnf = false;
+        let mut clauses: Vec<(usize, Vec<i32>)> = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|_| SolverError::IOError)?;
+            let mut iter = line.split_whitespace().peekable();
+            match iter.peek() {
+                None => continue,
+                Some(s) if s.starts_with('c') => continue,
+                Some(&"p") => {
+                    gcnf = line.contains("gcnf");
+                    num_vars = iter
+                        .nth(2)
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .ok_or(SolverError::IOError)?;
+                    continue;
+                }
+                _ => (),
+            }
+            let g = if gcnf {
+                iter.next()
+                    .and_then(|s| s.strip_prefix('{'))
+                    .and_then(|s| s.strip_suffix('}'))
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .ok_or(SolverError::IOError)?
+            } else {
+                clauses.len() + 1
+            };
+            let mut v: Vec<i32> = Vec::new();
+            for s in iter {
+                match s.parse::<i32>() {
+                    Ok(0) => break,
+                    Ok(val) => v.push(val),
+                    Err(_) => return Err(SolverError::IOError),
+                }
+            }
+            clauses.push((g, v));
+        }
+        let mut config = Config::from(path.to_string_lossy().as_ref());
+        config.quiet_mode = true;
+        MusExtractor::new(&config, num_vars, clauses)
+    }
+}
+
+impl MusExtractor {
+    /// make an extractor for clauses tagged by group numbers.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::OutOfRange` if a literal is out of range for var index.
+    pub fn new(
+        config: &Config,
+        num_vars: usize,
+        clauses: Vec<(usize, Vec<i32>)>,
+    ) -> Result<MusExtractor, SolverError> {
+        let num_groups = clauses.iter().map(|(g, _)| *g).max().unwrap_or(0);
+        let cnf = CNFDescription {
+            num_of_variables: num_vars + num_groups,
+            num_of_clauses: clauses.len(),
+            ..CNFDescription::default()
+        };
+        let mut solver = Solver::instantiate(config, &cnf);
+        // Selector vars must not be eliminated.
+        for g in 1..=num_groups {
+            solver.freeze(num_vars + g)?;
+        }
+        let mut group = vec![Vec::new(); num_groups + 1];
+        let mut occurs = vec![Vec::new(); 2 * (num_vars + 1)];
+        let mut inconsistent = false;
+        for (i, (g, v)) in clauses.iter().enumerate() {
+            let mut guarded = v.clone();
+            if 0 < *g {
+                guarded.push(-((num_vars + *g) as i32));
+            }
+            for l in v.iter() {
+                if *l == 0 || num_vars < l.unsigned_abs() as usize {
+                    return Err(SolverError::OutOfRange);
+                }
+                occurs[usize::from(Lit::from(*l))].push(i);
+            }
+            group[*g].push(i);
+            match solver.add_clause(guarded) {
+                Ok(_) => (),
+                // Only hard clauses can make the solver inconsistent.
+                Err(SolverError::Inconsistent) => inconsistent = true,
+                Err(e) => return Err(e),
+            }
+        }
+        let mut state = vec![GroupState::Removed; num_groups + 1];
+        for (g, s) in state.iter_mut().enumerate().skip(1) {
+            if !group[g].is_empty() {
+                *s = GroupState::Unknown;
+            }
+        }
+        Ok(MusExtractor {
+            solver,
+            num_vars,
+            clause: clauses,
+            group,
+            occurs,
+            state,
+            inconsistent,
+            num_solve: 0,
+            num_rotated: 0,
+        })
+    }
+    /// return the groups in a MUS, or `None` if the problem is satisfiable.
+    /// The empty vector means that the hard clauses are unsatisfiable.
+    ///
+    /// # Errors
+    ///
+    /// if the solver fails by an internal error.
+    pub fn extract(&mut self) -> Result<Option<Vec<usize>>, SolverError> {
+        if self.inconsistent {
+            return Ok(Some(Vec::new()));
+        }
+        if let Certificate::SAT(_) = self.solve(0)? {
+            return Ok(None);
+        }
+        self.refine(0);
+        while let Some(g) = (1..self.state.len())
+            .rev()
+            .find(|g| self.state[*g] == GroupState::Unknown)
+        {
+            match self.solve(g)? {
+                Certificate::UNSAT => {
+                    self.state[g] = GroupState::Removed;
+                    self.refine(g);
+                }
+                Certificate::SAT(model) => {
+                    self.state[g] = GroupState::Necessary;
+                    self.rotate(g, model);
+                }
+                // No budget is set on the solver.
+                Certificate::UNKNOWN => return Err(SolverError::UndescribedError),
+            }
+        }
+        Ok(Some(
+            (1..self.state.len())
+                .filter(|g| self.state[*g] == GroupState::Necessary)
+                .collect(),
+        ))
+    }
+    /// return the number of vars in the problem.
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+    /// return the clauses in a group.
+    pub fn clauses(&self, g: usize) -> Vec<&[i32]> {
+        self.group[g]
+            .iter()
+            .map(|i| self.clause[*i].1.as_slice())
+            .collect()
+    }
+    /// solve the problem with the groups not removed except `skip`.
+    fn solve(&mut self, skip: usize) -> Result<Certificate, SolverError> {
+        let assumptions = (1..self.state.len())
+            .filter(|g| *g != skip && self.state[*g] != GroupState::Removed)
+            .map(|g| (self.num_vars + g) as i32)
+            .collect::<Vec<_>>();
+        self.num_solve += 1;
+        self.solver.solve_with_assumptions(&assumptions)
+    }
+    /// remove the unknown groups which don't appear in the last UNSAT core.
+    fn refine(&mut self, skip: usize) {
+        let mut used = vec![false; self.state.len()];
+        for a in self.solver.failed_assumptions() {
+            used[a as usize - self.num_vars] = true;
+        }
+        for (g, s) in self.state.iter_mut().enumerate().skip(1) {
+            if g != skip && *s == GroupState::Unknown && !used[g] {
+                *s = GroupState::Removed;
+            }
+        }
+    }
+    /// find other necessary groups by flipping a var in a model which falsifies only group `g`.
+    fn rotate(&mut self, g: usize, model: Vec<i32>) {
+        let falsified =
+            |c: &[i32], model: &[i32]| c.iter().all(|l| model[l.unsigned_abs() as usize - 1] != *l);
+        let mut queue = vec![(g, model)];
+        while let Some((g, mut model)) = queue.pop() {
+            let targets = self.group[g]
+                .iter()
+                .filter(|i| falsified(&self.clause[**i].1, &model))
+                .copied()
+                .collect::<Vec<_>>();
+            for i in targets.iter() {
+                for l in self.clause[*i].1.iter() {
+                    // Flipping `l` must satisfy all the falsified clauses in `g`.
+                    if targets.iter().any(|j| !self.clause[*j].1.contains(l)) {
+                        continue;
+                    }
+                    let vi = l.unsigned_abs() as usize - 1;
+                    model[vi] = -model[vi];
+                    let mut found = None;
+                    for j in self.occurs[usize::from(!Lit::from(*l))].iter() {
+                        let (h, ref c) = self.clause[*j];
+                        if (0 < h && self.state[h] == GroupState::Removed) || !falsified(c, &model)
+                        {
+                            continue;
+                        }
+                        match found {
+                            _ if h == 0 || h == g => {
+                                found = None;
+                                break;
+                            }
+                            Some(k) if k != h => {
+                                found = None;
+                                break;
+                            }
+                            _ => found = Some(h),
+                        }
+                    }
+                    if let Some(h) = found {
+                        if self.state[h] == GroupState::Unknown {
+                            self.state[h] = GroupState::Necessary;
+                            self.num_rotated += 1;
+                            queue.push((h, model.clone()));
+                        }
+                    }
+                    model[vi] = -model[vi];
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(clauses: Vec<(usize, Vec<i32>)>) -> Option<Vec<usize>> {
+        let num_vars = clauses
+            .iter()
+            .flat_map(|(_, c)| c.iter())
+            .map(|l| l.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0);
+        let mut config = Config::default();
+        config.quiet_mode = true;
+        MusExtractor::new(&config, num_vars, clauses)
+            .expect("can't build")
+            .extract()
+            .expect("failed")
+    }
+
+    #[test]
+    fn test_mus() {
+        // 1, 3 and 4 make the MUS.
+        let cnf = vec![
+            (1, vec![1, 2]),
+            (2, vec![1, 3]),
+            (3, vec![-1, 2]),
+            (4, vec![-2]),
+            (5, vec![2, 3, 4]),
+            (6, vec![1, -2]),
+        ];
+        assert_eq!(extract(cnf), Some(vec![1, 3, 4]));
+        assert_eq!(extract(vec![(1, vec![1, 2]), (2, vec![-1])]), None);
+        // groups with hard clauses
+        let gcnf = vec![
+            (0, vec![1, 2]),
+            (0, vec![-1, 2]),
+            (1, vec![-2, 3]),
+            (1, vec![4]),
+            (2, vec![-3, -4]),
+            (3, vec![-2, 5]),
+        ];
+        assert_eq!(extract(gcnf), Some(vec![1, 2]));
+        assert_eq!(
+            extract(vec![(0, vec![1]), (0, vec![-1]), (1, vec![2])]),
+            Some(vec![])
+        );
+    }
+
+    #[cfg_attr(not(feature = "no_IO"), test)]
+    fn test_mus_of_cnf() {
+        let mut mus =
+            MusExtractor::try_from(&PathBuf::from("tests/unsat.cnf")).expect("can't load");
+        let groups = mus.extract().expect("failed").expect("not unsat");
+        let clauses = groups
+            .iter()
+            .flat_map(|g| mus.clauses(*g))
+            .map(|c| c.to_vec())
+            .collect::<Vec<_>>();
+        // Every proper subset is satisfiable.
+        for i in 0..clauses.len() {
+            let mut subset = clauses.clone();
+            subset.remove(i);
+            assert!(matches!(
+                Certificate::try_from(subset),
+                Ok(Certificate::SAT(_))
+            ));
+        }
+        assert!(matches!(
+            Certificate::try_from(clauses),
+            Ok(Certificate::UNSAT)
+        ));
+    }
+}
@@ -1,7 +1,7 @@
 //! Crate `restart` provides restart heuristics.
 use {
     crate::{solver::SolverEvent, types::*},
-    std::fmt,
+    std::{fmt, str::FromStr},
 };
 
 /// API for restart condition.
@@ -15,31 +15,29 @@ trait ProgressEvaluator {
 }
 
 /// Update progress observer sub-modules
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum ProgressUpdate {
     Counter,
     Temperature(f64),
-
-    #[cfg(feature = "progress_ACC")]
     ACC(f64),
-
     ASG(usize),
     LBD(u16),
     Luby,
-
-    #[cfg(feature = "progress_MLD")]
     MLD(u16),
 
     Remain(usize),
 }
 
-/// Restart modes
+/// Restart modes, used as restart policies in [`Config`](`crate::config::Config`)
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum RestartMode {
     /// Controlled by Glucose-like forcing and blocking restart scheme
     Dynamic = 0,
     /// Controlled by a good old scheme
     Luby,
+    /// Controlled by a geometric series of intervals like MiniSat
+    Geometric,
     /// Controlled by CaDiCal-like Geometric Stabilizer
     Stabilize,
 
@@ -47,6 +45,30 @@ pub enum RestartMode {
     Bucket,
 }
 
+impl fmt::Display for RestartMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RestartMode::Dynamic => f.pad("dynamic"),
+            RestartMode::Luby => f.pad("luby"),
+            RestartMode::Geometric => f.pad("geometric"),
+            RestartMode::Stabilize => f.pad("stabilize"),
+        }
+    }
+}
+
+impl FromStr for RestartMode {
+    type Err = SolverError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dynamic" => Ok(RestartMode::Dynamic),
+            "luby" => Ok(RestartMode::Luby),
+            "geometric" => Ok(RestartMode::Geometric),
+            "stabilize" => Ok(RestartMode::Stabilize),
+            _ => Err(SolverError::OutOfRange),
+        }
+    }
+}
+
 type RestarterExports = (usize, usize, usize, usize, usize);
 
 /// API for [`restart`](`crate::solver::RestartIF::restart`) and [`stabilize`](`crate::solver::RestartIF::stabilize`).
@@ -174,7 +196,6 @@ impl ProgressEvaluator for ProgressLBD {
     fn shift(&mut self) {}
 }
 
-#[cfg(feature = "progress_MLD")]
 /// An EMA of Maximum LBD of a Dependent graph, used in conflict analyze
 #[derive(Debug, Clone, Copy)]
 pub struct ProgressMLD {
@@ -182,40 +203,38 @@ pub struct ProgressMLD {
     ema: Ema2,
     num: usize,
     sum: usize,
-    scaling: f64,
     threshold: f64,
 }
 
-#[cfg(feature = "progress_MLD")]
 impl Default for ProgressMLD {
     fn default() -> ProgressMLD {
         ProgressMLD {
-            enable: true,
+            enable: false,
             ema: Ema2::new(1),
             num: 0,
             sum: 0,
-            scaling: 0.20,
             threshold: 2.0,
         }
     }
 }
 
-#[cfg(feature = "progress_MLD")]
 impl Instantiate for ProgressMLD {
     fn instantiate(config: &Config, _: &CNFDescription) -> Self {
         ProgressMLD {
+            enable: config.use_mld(),
             ema: Ema2::new(config.rst_lbd_len).with_slow(config.rst_lbd_slw),
-            scaling: config.rst_mld_scl,
             threshold: config.rst_mld_thr,
             ..ProgressMLD::default()
         }
     }
 }
 
-#[cfg(feature = "progress_MLD")]
 impl EmaIF for ProgressMLD {
     type Input = u16;
     fn update(&mut self, d: Self::Input) {
+        if !self.enable {
+            return;
+        }
         self.num += 1;
         self.sum += d as usize;
         self.ema.update(d as f64);
@@ -228,7 +247,6 @@ impl EmaIF for ProgressMLD {
     }
 }
 
-#[cfg(feature = "progress_MLD")]
 impl ProgressEvaluator for ProgressMLD {
     fn is_active(&self) -> bool {
         self.enable && self.threshold < self.ema.trend()
@@ -236,14 +254,12 @@ impl ProgressEvaluator for ProgressMLD {
     fn shift(&mut self) {}
 }
 
-#[cfg(feature = "progress_MLD")]
 impl ProgressMLD {
     pub fn get_slow(&self) -> f64 {
         self.ema.get_slow()
     }
 }
 
-#[cfg(feature = "progress_ACC")]
 /// An EMA of Activity-based Conflict Correlation, used for forcing restart.
 #[derive(Debug, Clone, Copy)]
 struct ProgressACC {
@@ -262,11 +278,10 @@ struct ProgressACC {
     threshold: f64,
 }
 
-#[cfg(feature = "progress_ACC")]
 impl Default for ProgressACC {
     fn default() -> ProgressACC {
         ProgressACC {
-            enable: true,
+            enable: false,
             ema: Ema2::new(1),
             num: 0,
             sum: 0.0,
@@ -278,10 +293,10 @@ impl Default for ProgressACC {
     }
 }
 
-#[cfg(feature = "progress_ACC")]
 impl Instantiate for ProgressACC {
     fn instantiate(config: &Config, _: &CNFDescription) -> Self {
         ProgressACC {
+            enable: config.use_acc(),
             ema: Ema2::new(config.rst_lbd_len).with_slow(config.rst_lbd_slw),
             threshold: config.rst_ccc_thr,
             ..ProgressACC::default()
@@ -289,10 +304,12 @@ impl Instantiate for ProgressACC {
     }
 }
 
-#[cfg(feature = "progress_ACC")]
 impl EmaIF for ProgressACC {
     type Input = f64;
     fn update(&mut self, d: Self::Input) {
+        if !self.enable {
+            return;
+        }
         self.inc += 1;
         self.val += d;
     }
@@ -304,7 +321,6 @@ impl EmaIF for ProgressACC {
     }
 }
 
-#[cfg(feature = "progress_ACC")]
 impl ProgressEvaluator for ProgressACC {
     // Smaller core, larger value
     fn is_active(&self) -> bool {
@@ -384,7 +400,7 @@ impl Default for ProgressLuby {
 impl Instantiate for ProgressLuby {
     fn instantiate(config: &Config, _: &CNFDescription) -> Self {
         ProgressLuby {
-            enable: config.use_luby(),
+            enable: config.rst_mode == RestartMode::Luby,
             step: config.rst_step,
             ..ProgressLuby::default()
         }
@@ -456,6 +472,60 @@ impl ProgressLuby {
     */
 }
 
+/// A geometric series of restart intervals.
+#[derive(Debug, Clone, Copy)]
+struct ProgressGeometric {
+    enable: bool,
+    active: bool,
+    next_restart: f64,
+    restart_inc: f64,
+}
+
+impl Default for ProgressGeometric {
+    fn default() -> Self {
+        ProgressGeometric {
+            enable: false,
+            active: false,
+            next_restart: 100.0,
+            restart_inc: 1.5,
+        }
+    }
+}
+
+impl Instantiate for ProgressGeometric {
+    fn instantiate(config: &Config, _: &CNFDescription) -> Self {
+        ProgressGeometric {
+            enable: config.rst_mode == RestartMode::Geometric,
+            next_restart: config.rst_step as f64,
+            restart_inc: config.rst_geo_inc,
+            ..ProgressGeometric::default()
+        }
+    }
+}
+
+impl EmaIF for ProgressGeometric {
+    type Input = usize;
+    fn update(&mut self, now: usize) {
+        if !self.enable {
+            return;
+        }
+        self.active = self.next_restart < now as f64;
+    }
+    fn get(&self) -> f64 {
+        self.next_restart
+    }
+}
+
+impl ProgressEvaluator for ProgressGeometric {
+    fn is_active(&self) -> bool {
+        self.enable && self.active
+    }
+    fn shift(&mut self) {
+        self.active = false;
+        self.next_restart *= self.restart_inc;
+    }
+}
+
 /// An implementation of CaDiCaL-style blocker.
 /// This is a stealth blocker between the other evaluators and solver;
 /// the other evaluators work as if this blocker doesn't exist.
@@ -499,7 +569,7 @@ impl Default for GeometricStabilizer {
 impl Instantiate for GeometricStabilizer {
     fn instantiate(config: &Config, _: &CNFDescription) -> Self {
         GeometricStabilizer {
-            enable: config.use_stabilize(),
+            enable: config.use_stabilize() || config.rst_mode == RestartMode::Stabilize,
             ..GeometricStabilizer::default()
         }
     }
@@ -562,20 +632,12 @@ impl GeometricStabilizer {
     fn reset_progress(&mut self) {
         self.reset_requested = true;
     }
-
-    #[cfg(feature = "luby_blocking")]
     fn new(enable: bool, scale: usize) -> Self {
         GeometricStabilizer {
             enable,
-            active: false,
-            longest_span: 1,
-            luby: LubySeries::default(),
-            num_cycle: 0,
-            num_shift: 0,
             next_trigger: scale,
-            reset_requested: false,
-            step: 1,
             scale,
+            ..GeometricStabilizer::default()
         }
     }
 }
@@ -667,27 +729,23 @@ impl ProgressEvaluator for ProgressBucket {
 /// `Restarter` provides restart API and holds data about restart conditions.
 #[derive(Debug, Clone)]
 pub struct Restarter {
-    #[cfg(feature = "progress_ACC")]
     acc: ProgressACC,
-
     asg: ProgressASG,
 
     #[cfg(feature = "progress_Bucket")]
     bkt: ProgressBucket,
 
+    geo: ProgressGeometric,
     lbd: ProgressLBD,
-
-    #[cfg(feature = "progress_MLD")]
     pub mld: ProgressMLD,
 
     // pub blvl: ProgressLVL,
     // pub clvl: ProgressLVL,
     luby: ProgressLuby,
-
-    #[cfg(feature = "luby_blocking")]
     luby_blocking: GeometricStabilizer,
-
     stb: GeometricStabilizer,
+    /// restart policy
+    mode: RestartMode,
     after_restart: usize,
     restart_step: usize,
     initial_restart_step: usize,
@@ -703,27 +761,22 @@ pub struct Restarter {
 impl Default for Restarter {
     fn default() -> Restarter {
         Restarter {
-            #[cfg(feature = "progress_ACC")]
             acc: ProgressACC::default(),
-
             asg: ProgressASG::default(),
 
             #[cfg(feature = "progress_Bucket")]
             bkt: ProgressBucket::default(),
 
+            geo: ProgressGeometric::default(),
             lbd: ProgressLBD::default(),
-
-            #[cfg(feature = "progress_MLD")]
             mld: ProgressMLD::default(),
 
             // blvl: ProgressLVL::default(),
             // clvl: ProgressLVL::default(),
             luby: ProgressLuby::default(),
-
-            #[cfg(feature = "luby_blocking")]
-            luby_blocking: GeometricStabilizer::new(true, 1000),
-
+            luby_blocking: GeometricStabilizer::new(false, 1000),
             stb: GeometricStabilizer::default(),
+            mode: RestartMode::Dynamic,
             after_restart: 0,
             restart_step: 0,
             initial_restart_step: 0,
@@ -738,23 +791,22 @@ impl Default for Restarter {
 impl Instantiate for Restarter {
     fn instantiate(config: &Config, cnf: &CNFDescription) -> Self {
         Restarter {
-            #[cfg(feature = "progress_ACC")]
             acc: ProgressACC::instantiate(config, cnf),
-
             asg: ProgressASG::instantiate(config, cnf),
 
             #[cfg(feature = "progress_Bucket")]
             bkt: ProgressBucket::instantiate(config, cnf),
 
+            geo: ProgressGeometric::instantiate(config, cnf),
             lbd: ProgressLBD::instantiate(config, cnf),
-
-            #[cfg(feature = "progress_MLD")]
             mld: ProgressMLD::instantiate(config, cnf),
 
             // blvl: ProgressLVL::instantiate(config, cnf),
             // clvl: ProgressLVL::instantiate(config, cnf),
             luby: ProgressLuby::instantiate(config, cnf),
+            luby_blocking: GeometricStabilizer::new(config.use_luby_blocking(), 1000),
             stb: GeometricStabilizer::instantiate(config, cnf),
+            mode: config.rst_mode,
             restart_step: config.rst_step,
             initial_restart_step: config.rst_step,
             stb_expansion_factor: config.rst_stb_exp,
@@ -765,10 +817,7 @@ impl Instantiate for Restarter {
     fn handle(&mut self, e: SolverEvent) {
         match e {
             SolverEvent::Assert(_) => {
-                #[cfg(feature = "luby_blocking")]
-                {
-                    self.luby_blocking.reset_progress();
-                }
+                self.luby_blocking.reset_progress();
                 // self.stb.reset_progress();
             }
             SolverEvent::Restart => {
@@ -796,47 +845,22 @@ pub enum RestartDecision {
 impl RestartIF for Restarter {
     type Exports = RestarterExports;
     #[inline]
-    #[allow(clippy::collapsible_if)]
     fn restart(&mut self) -> Option<RestartDecision> {
-        if self.luby.is_active() {
-            self.luby.shift();
-            return Some(RestartDecision::Force);
-        }
-        if self.after_restart < self.restart_step {
-            return None;
-        }
-
-        #[cfg(feature = "progress_ACC")]
-        {
-            self.acc.shift();
-        }
-
-        if self.stb.active {
-            return Some(RestartDecision::Stabilize);
-        }
-
-        if self.asg.is_active() {
-            self.num_block += 1;
-            self.after_restart = 0;
-
-            #[cfg(feature = "luby_blocking")]
-            {
-                self.luby_blocking.update(0);
-                self.restart_step = self.initial_restart_step
-                    * self.luby_blocking.span()
-                    * self.luby_blocking.scale;
+        match self.mode {
+            RestartMode::Luby if self.luby.is_active() => {
+                self.luby.shift();
+                Some(RestartDecision::Force)
             }
-            return Some(RestartDecision::Block);
-        }
-
-        if self.lbd.is_active() {
-            #[cfg(feature = "luby_blocking")]
-            {
-                self.restart_step = self.initial_restart_step;
+            RestartMode::Geometric if self.geo.is_active() => {
+                self.geo.shift();
+                Some(RestartDecision::Force)
             }
-            return Some(RestartDecision::Force);
+            RestartMode::Luby | RestartMode::Geometric => None,
+            // Restart only at the ends of stabilization phases.
+            RestartMode::Stabilize if self.stb.active => Some(RestartDecision::Stabilize),
+            RestartMode::Stabilize => Some(RestartDecision::Postpone),
+            _ => self.restart_dynamically(),
         }
-        Some(RestartDecision::Postpone)
     }
     fn stabilize(&mut self, now: usize) -> Option<(bool, bool)> {
         self.stb.update(now)
@@ -846,14 +870,12 @@ impl RestartIF for Restarter {
             ProgressUpdate::Counter => {
                 self.after_restart += 1;
                 self.luby.update(self.after_restart);
+                self.geo.update(self.after_restart);
             }
             ProgressUpdate::Temperature(c) => {
                 self.stb.depth = 1.0 + self.stb_expansion_factor * c;
             }
-
-            #[cfg(feature = "progress_ACC")]
             ProgressUpdate::ACC(val) => self.acc.update(val),
-
             ProgressUpdate::ASG(val) => self.asg.update(val),
             ProgressUpdate::LBD(val) => {
                 if !self.stb.active {
@@ -861,10 +883,7 @@ impl RestartIF for Restarter {
                 }
             }
             ProgressUpdate::Luby => self.luby.update(0),
-
-            #[cfg(feature = "progress_MLD")]
             ProgressUpdate::MLD(val) => self.mld.update(val),
-
             ProgressUpdate::Remain(val) => {
                 self.asg.num_var = val;
             }
@@ -872,6 +891,37 @@ impl RestartIF for Restarter {
     }
 }
 
+impl Restarter {
+    /// check Glucose-like blocking and forcing restart condition.
+    fn restart_dynamically(&mut self) -> Option<RestartDecision> {
+        if self.after_restart < self.restart_step {
+            return None;
+        }
+        self.acc.shift();
+        if self.stb.active {
+            return Some(RestartDecision::Stabilize);
+        }
+        if self.asg.is_active() {
+            self.num_block += 1;
+            self.after_restart = 0;
+            if self.luby_blocking.enable {
+                self.luby_blocking.update(0);
+                self.restart_step = self.initial_restart_step
+                    * self.luby_blocking.span()
+                    * self.luby_blocking.scale;
+            }
+            return Some(RestartDecision::Block);
+        }
+        if self.lbd.is_active() || self.acc.is_active() {
+            if self.luby_blocking.enable {
+                self.restart_step = self.initial_restart_step;
+            }
+            return Some(RestartDecision::Force);
+        }
+        Some(RestartDecision::Postpone)
+    }
+}
+
 impl Export<RestarterExports, (RestartMode, usize)> for Restarter {
     /// exports:
     ///  1. the number of blocking in non-stabilization
@@ -900,8 +950,7 @@ impl Export<RestarterExports, (RestartMode, usize)> for Restarter {
         (
             match self.stb.active {
                 true => RestartMode::Stabilize,
-                false if self.luby.enable => RestartMode::Luby,
-                false => RestartMode::Dynamic,
+                false => self.mode,
             },
             self.stb.span(),
         )
@@ -988,6 +1037,34 @@ mod tests {
         }
     }
     #[test]
+    fn test_progress_geometric() {
+        let mut geo = ProgressGeometric {
+            enable: true,
+            next_restart: 100.0,
+            restart_inc: 1.5,
+            ..ProgressGeometric::default()
+        };
+        for v in &[100, 150, 225, 337] {
+            geo.update(*v);
+            assert!(!geo.is_active());
+            geo.update(*v + 1);
+            assert!(geo.is_active());
+            geo.shift();
+        }
+    }
+    #[test]
+    fn test_restart_mode() {
+        for mode in &[
+            RestartMode::Dynamic,
+            RestartMode::Luby,
+            RestartMode::Geometric,
+            RestartMode::Stabilize,
+        ] {
+            assert_eq!(mode.to_string().parse::<RestartMode>(), Ok(*mode));
+        }
+        assert!("static".parse::<RestartMode>().is_err());
+    }
+    #[test]
     fn test_luby_series() {
         let mut luby = LubySeries::default();
         let v = vec![1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8];
@@ -6,17 +6,67 @@ use {
         conflict::handle_conflict,
         restart::{ProgressUpdate, RestartDecision, RestartIF, Restarter},
         vivify::vivify,
-        Certificate, Solver, SolverEvent, SolverResult,
+        Certificate, SatSolverIF, Solver, SolverEvent, SolverResult,
     },
     crate::{
-        assign::{AssignIF, AssignStack, PropagateIF, VarManipulateIF, VarRewardIF, VarSelectIF},
-        cdb::{ClauseDB, ClauseDBIF},
-        processor::{EliminateIF, Eliminator},
-        state::{Stat, State, StateIF},
+        assign::{
+            AssignIF, AssignStack, ExternalPropagateIF, ExternalPropagator, PropagateIF,
+            RephaseTarget, VarManipulateIF, VarRewardIF, VarSelectIF,
+        },
+        cdb::{ClauseDB, ClauseDBIF, StochasticLocalSearchIF},
+        processor::{self, EliminateIF, Eliminator},
+        state::{Hook, LearntCallback, Stat, State, StateIF, TerminateCallback},
         types::*,
     },
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    },
 };
 
+/// Resource limits on an invocation of [`solve`](`crate::solver::SolveIF::solve`).
+/// Each limit is counted from the start of the invocation.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Budget {
+    /// the number of conflicts
+    pub conflicts: Option<usize>,
+    /// the number of decisions
+    pub decisions: Option<usize>,
+    /// the number of propagated literals
+    pub propagations: Option<usize>,
+    /// wall-clock time
+    pub time: Option<Duration>,
+}
+
+/// The limits of a [`Budget`] on the counters, fixed at the start of an invocation.
+struct Limits {
+    conflicts: Option<usize>,
+    decisions: Option<usize>,
+    propagations: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl Limits {
+    fn new(budget: &Budget, asg: &AssignStack, state: &State) -> Self {
+        Limits {
+            conflicts: budget.conflicts.map(|n| asg.num_conflict + n),
+            decisions: budget.decisions.map(|n| state[Stat::Decision] + n),
+            propagations: budget.propagations.map(|n| asg.exports().1 + n),
+            deadline: budget.time.map(|t| Instant::now() + t),
+        }
+    }
+    /// return `true` if one of the limits is exhausted.
+    fn is_exhausted(&self, asg: &AssignStack, state: &State) -> bool {
+        matches!(self.conflicts, Some(n) if n <= asg.num_conflict)
+            || matches!(self.decisions, Some(n) if n <= state[Stat::Decision])
+            || matches!(self.propagations, Some(n) if n <= asg.exports().1)
+            || matches!(self.deadline, Some(t) if t <= Instant::now())
+    }
+}
+
 /// API to [`solve`](`crate::solver::SolveIF::solve`) SAT problems.
 pub trait SolveIF {
     /// search an assignment.
@@ -25,6 +75,150 @@ pub trait SolveIF {
     ///
     /// if solver becomes inconsistent by an internal error.
     fn solve(&mut self) -> SolverResult;
+    /// search an assignment under `assumptions`, which are valid only in this invocation.
+    /// Learnt clauses are kept for the next invocation.
+    /// If it returns `UNSAT`, [`failed_assumptions`](`crate::solver::SolveIF::failed_assumptions`)
+    /// returns the assumptions responsible for it. Once the problem is refuted without
+    /// assumptions, every later invocation returns `UNSAT`.
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::OutOfRange` if an assumption is out of range for var index.
+    /// * `SolverError::Inconsistent` if the reactivation of an eliminated var makes the solver
+    ///   inconsistent.
+    /// * if solver becomes inconsistent by an internal error.
+    fn solve_with_assumptions(&mut self, assumptions: &[i32]) -> SolverResult;
+    /// return the subset of assumptions which made the last invocation of
+    /// [`solve_with_assumptions`](`crate::solver::SolveIF::solve_with_assumptions`) return `UNSAT`.
+    /// The empty vector means that the problem is unsatisfiable without any assumption.
+    /// The activation literals of constraint scopes aren't included.
+    fn failed_assumptions(&self) -> Vec<i32>;
+    /// return the indices, from zero in the given order, of the given clauses in an UNSAT core
+    /// found by the last invocation. **Requires `Config::use_core`**
+    /// Return `None` if it didn't refute the problem without assumptions,
+    /// or the refutation depends on a step which can't be traced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut config = Config::default();
+    /// config.use_core = true;
+    /// let cnf = vec![vec![1, 2], vec![-1, 2], vec![3, 4], vec![1, -2], vec![-1, -2]];
+    /// let mut s = Solver::try_from((config, cnf.as_ref())).expect("can't build");
+    /// assert_eq!(s.solve(), Ok(Certificate::UNSAT));
+    /// assert_eq!(s.unsat_core(), Some(vec![0, 1, 3, 4]));
+    ///```
+    fn unsat_core(&self) -> Option<Vec<usize>>;
+    /// return a handle to stop the search from another thread.
+    /// After storing `true` to it, the running invocation returns `Certificate::UNKNOWN`
+    /// at root level, and the handle is reset for the next invocation.
+    /// If no invocation is running, the next one is stopped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::{convert::TryFrom, sync::atomic::Ordering};
+    ///
+    /// let mut s = Solver::try_from("tests/sample.cnf").expect("can't load");
+    /// let handle = s.interrupt_handle();
+    /// handle.store(true, Ordering::Relaxed);
+    /// assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn interrupt_handle(&self) -> Arc<AtomicBool>;
+    /// set resource limits on the next invocation, which returns `Certificate::UNKNOWN` when
+    /// one of them is exhausted. The limits are cleared after the invocation.
+    /// They are checked at every step of the search loop and after preprocessing;
+    /// a run of the eliminator or a local search isn't stopped in the middle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use splr::solver::Budget;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from("tests/sample.cnf").expect("can't load");
+    /// s.set_budget(Budget {
+    ///     conflicts: Some(0),
+    ///     ..Budget::default()
+    /// });
+    /// assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn set_budget(&mut self, budget: Budget);
+    /// set a callback to stop the search, which is polled at every conflict and before
+    /// every local search.
+    /// When it returns `true`, the invocation returns `Certificate::UNKNOWN` at root level.
+    /// `None` removes the callback.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from("tests/sample.cnf").expect("can't load");
+    /// s.set_terminate(Some(Box::new(|| true)));
+    /// assert_eq!(s.solve(), Ok(Certificate::UNKNOWN));
+    /// s.set_terminate(None);
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn set_terminate(&mut self, callback: Option<TerminateCallback>);
+    /// set a callback which receives every learnt clause derived by conflict analysis
+    /// if it has `max_len` literals at most or its LBD is `max_lbd` at most.
+    /// `None` removes the callback.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::{convert::TryFrom, sync::{Arc, Mutex}};
+    ///
+    /// let mut s = Solver::try_from("tests/sample.cnf").expect("can't load");
+    /// let learnts = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&learnts);
+    /// s.set_learnt_callback(2, 0, Some(Box::new(move |c: &[i32]| sink.lock().unwrap().push(c.to_vec()))));
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// assert!(learnts.lock().unwrap().iter().all(|c| c.len() <= 2));
+    /// ```
+    fn set_learnt_callback(
+        &mut self,
+        max_len: usize,
+        max_lbd: usize,
+        callback: Option<LearntCallback>,
+    );
+    /// return a handle to give clauses from outside, e.g. learnt clauses exported by
+    /// another solver for the same problem. The clauses pushed to it are added as learnt
+    /// clauses at the next restart, so they must be implied by the problem.
+    /// Clauses containing an unknown or eliminated var are dropped, and so are all clauses
+    /// while a proof or an UNSAT core is recorded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from("tests/uf8.cnf").expect("can't load");
+    /// s.import_handle().lock().unwrap().push(vec![1, 2]);
+    /// assert!(matches!(s.solve(), Ok(Certificate::SAT(_))));
+    /// ```
+    fn import_handle(&self) -> Arc<Mutex<Vec<Vec<i32>>>>;
+    /// connect an external propagator, which works as a theory solver in the search.
+    /// `None` disconnects the current one. See [`ExternalPropagator`](`crate::assign::ExternalPropagator`).
+    ///
+    /// # Errors
+    ///
+    /// * `SolverError::ProofUnavailable` if a proof or an UNSAT core is recorded.
+    fn connect_propagator(
+        &mut self,
+        propagator: Option<Box<dyn ExternalPropagator + Send>>,
+    ) -> Result<&mut Solver, SolverError>;
 }
 
 macro_rules! RESTART {
@@ -48,173 +242,300 @@ impl SolveIF for Solver {
     /// }
     ///```
     fn solve(&mut self) -> SolverResult {
+        self.solve_with_assumptions(&[])
+    }
+    /// # Examples
+    ///
+    /// ```
+    /// use splr::*;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut s = Solver::try_from((Config::default(), vec![vec![1, 2], vec![-1, 3], vec![-2, 3]].as_ref()))
+    ///     .expect("can't build");
+    /// assert_eq!(s.solve_with_assumptions(&[-3, 1]), Ok(Certificate::UNSAT));
+    /// assert!(s.failed_assumptions().contains(&-3));
+    /// assert!(matches!(s.solve_with_assumptions(&[3]), Ok(Certificate::SAT(_))));
+    ///```
+    fn solve_with_assumptions(&mut self, assumptions: &[i32]) -> SolverResult {
+        // Neither new clauses nor assumptions make a refuted problem satisfiable.
+        if self.state.inconsistent {
+            self.state.conflicts.clear();
+            return Ok(Certificate::UNSAT);
+        }
+        for a in assumptions.iter() {
+            // Vars eliminated by a previous invocation come back with their clauses.
+            self.reactivate(a.unsigned_abs() as usize)?;
+        }
+        let Solver {
+            ref mut asg,
+            ref mut state,
+            ..
+        } = self;
+        state.conflicts.clear();
+        // The longest trail in the last invocation isn't a trail under the new assumptions.
+        state.last_asg = 0;
+        // Every open constraint scope is enabled by its activation literal.
+        state.assumptions = state
+            .scopes
+            .iter()
+            .copied()
+            .chain(assumptions.iter().map(|a| Lit::from(*a)))
+            .collect();
+        for l in state.assumptions.iter() {
+            asg.var_mut(l.vi()).turn_on(Flag::ASSUMED);
+        }
+        let answer = solve_main(self);
         let Solver {
             ref mut asg,
             ref mut cdb,
-            ref mut elim,
-            ref mut rst,
             ref mut state,
+            ..
         } = self;
-        if cdb.check_size().is_err() {
-            return Err(SolverError::OutOfMemory);
+        for l in state.assumptions.iter() {
+            asg.var_mut(l.vi()).turn_off(Flag::ASSUMED);
         }
-        asg.num_asserted_vars = asg.stack_len();
-        state.progress_header();
-        state.progress(asg, cdb, elim, rst);
-        state.flush("");
-        state.flush("Preprocessing stage: ");
-        if 0 < asg.stack_len() {
-            elim.eliminate_satisfied_clauses(asg, cdb, false);
+        state.assumptions.clear();
+        state.budget = Budget::default();
+        if answer == Ok(Certificate::UNKNOWN) {
+            state.interrupt.store(false, Ordering::Relaxed);
         }
-        if elim.enable {
-            const USE_PRE_PROCESSING_ELIMINATOR: bool = true;
+        if answer == Ok(Certificate::UNSAT) && state.conflicts.is_empty() {
+            cdb.certificate_refutation(asg);
+            state.inconsistent = true;
+        }
+        // The proof should be saved even if it's timed out.
+        cdb.certified.flush()?;
+        answer
+    }
+    fn failed_assumptions(&self) -> Vec<i32> {
+        self.state
+            .conflicts
+            .iter()
+            .filter(|l| !self.state.scopes.contains(&!**l))
+            .map(|l| i32::from(!*l))
+            .collect()
+    }
+    fn unsat_core(&self) -> Option<Vec<usize>> {
+        self.cdb
+            .certified
+            .unsat_core()
+            .map(|v| v.iter().map(|id| id - 1).collect())
+    }
+    fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.state.interrupt)
+    }
+    fn set_budget(&mut self, budget: Budget) {
+        self.state.budget = budget;
+    }
+    fn set_terminate(&mut self, callback: Option<TerminateCallback>) {
+        self.state.terminate = Hook::new(callback);
+    }
+    fn set_learnt_callback(
+        &mut self,
+        max_len: usize,
+        max_lbd: usize,
+        callback: Option<LearntCallback>,
+    ) {
+        self.state.learnt_hook = Hook::new(callback);
+        self.state.learnt_hook_len = max_len;
+        self.state.learnt_hook_lbd = max_lbd;
+    }
+    fn import_handle(&self) -> Arc<Mutex<Vec<Vec<i32>>>> {
+        Arc::clone(&self.state.imports)
+    }
+    fn connect_propagator(
+        &mut self,
+        propagator: Option<Box<dyn ExternalPropagator + Send>>,
+    ) -> Result<&mut Solver, SolverError> {
+        // Theory clauses can't be verified.
+        if propagator.is_some() && self.cdb.certified.is_active() {
+            return Err(SolverError::ProofUnavailable);
+        }
+        self.asg.connect_propagator(propagator);
+        Ok(self)
+    }
+}
 
-            //
-            //## Propagate all trivial literals (an essential step)
-            //
-            // Set appropriate phases and push all the unit clauses to assign stack.
-            // To do so, we use eliminator's occur list.
-            // Thus we have to call `activate` and `prepare` firstly, to build occur lists.
-            // Otherwise all literals are assigned wrongly.
-            state.flush("phasing...");
-            elim.activate();
-            elim.prepare(asg, cdb, true);
-            for vi in 1..=asg.num_vars {
-                if asg.assign(vi).is_some() {
-                    continue;
-                }
-                if let Some((p, m)) = elim.stats(vi) {
-                    // We can't call `asg.assign_at_root_level(l)` even if p or m == 0.
-                    // This means we can't pick `!l`.
-                    // This becomes a problem in the case of incremental solving.
-                    #[cfg(not(feature = "incremental_solver"))]
-                    {
-                        if m == 0 {
-                            let l = Lit::from_assign(vi, true);
-                            if asg.assign_at_root_level(l).is_err() {
-                                return Ok(Certificate::UNSAT);
-                            }
-                        } else if p == 0 {
-                            let l = Lit::from_assign(vi, false);
-                            if asg.assign_at_root_level(l).is_err() {
-                                return Ok(Certificate::UNSAT);
-                            }
-                        }
-                    }
-                    asg.var_mut(vi).set(Flag::PHASE, m < p);
-                    elim.enqueue_var(asg, vi, false);
-                }
+/// run the preprocessor and the search engine under `state.assumptions`.
+fn solve_main(solver: &mut Solver) -> SolverResult {
+    let Solver {
+        ref mut asg,
+        ref mut cdb,
+        ref mut elim,
+        ref mut rst,
+        ref mut state,
+    } = solver;
+    if cdb.check_size().is_err() {
+        return Err(SolverError::OutOfMemory);
+    }
+    let limits = Limits::new(&state.budget, asg, state);
+    asg.num_asserted_vars = asg.stack_len();
+    state.progress_header();
+    state.progress(asg, cdb, elim, rst);
+    state.flush("");
+    state.flush("Preprocessing stage: ");
+    if 0 < asg.stack_len() {
+        elim.eliminate_satisfied_clauses(asg, cdb, false);
+    }
+    if elim.enable {
+        const USE_PRE_PROCESSING_ELIMINATOR: bool = true;
+
+        //
+        //## Propagate all trivial literals (an essential step)
+        //
+        // Set appropriate phases and push all the unit clauses to assign stack.
+        // To do so, we use eliminator's occur list.
+        // Thus we have to call `activate` and `prepare` firstly, to build occur lists.
+        // Otherwise all literals are assigned wrongly.
+        state.flush("phasing...");
+        elim.activate();
+        elim.prepare(asg, cdb, true);
+        for vi in 1..=asg.num_vars {
+            if asg.assign(vi).is_some() || asg.var(vi).is(Flag::ELIMINATED) {
+                continue;
             }
-            //
-            //## Run eliminator
-            //
-            if USE_PRE_PROCESSING_ELIMINATOR {
-                state.flush("simplifying...");
-                if elim.simplify(asg, cdb, state).is_err() {
-                    // Why inconsistent? Because the CNF contains a conflict, not an error!
-                    // Or out of memory.
-                    state.progress(asg, cdb, elim, rst);
-                    if cdb.check_size().is_err() {
-                        return Err(SolverError::OutOfMemory);
-                    }
-                    return Ok(Certificate::UNSAT);
-                }
-                for vi in 1..=asg.num_vars {
-                    if asg.assign(vi).is_some() || asg.var(vi).is(Flag::ELIMINATED) {
-                        continue;
-                    }
-                    match elim.stats(vi) {
-                        Some((_, 0)) => (),
-                        Some((0, _)) => (),
-                        Some((p, m)) if m * 10 < p => asg.var_mut(vi).turn_on(Flag::PHASE),
-                        Some((p, m)) if p * 10 < m => asg.var_mut(vi).turn_off(Flag::PHASE),
-                        _ => (),
-                    }
-                }
-                asg.initialize_reward(elim.sorted_iterator());
-                asg.rebuild_order();
+            if let Some((p, m)) = elim.stats(vi) {
+                // We can't call `asg.assign_at_root_level(l)` even if p or m == 0,
+                // because a solver can be re-used with clauses containing `!l`.
+                asg.var_mut(vi).set(Flag::PHASE, m < p);
+                elim.enqueue_var(asg, vi, false);
             }
-            elim.stop(asg, cdb);
         }
-
         //
-        //## Search
+        //## Run eliminator
         //
-        state.progress(asg, cdb, elim, rst);
-        let answer = search(asg, cdb, elim, rst, state);
-        state.progress(asg, cdb, elim, rst);
-        match answer {
-            Ok(true) => {
-                // As a preparation for incremental solving, we need to backtrack to the
-                // root level. So all assignments, including assignments to eliminated vars,
-                // are stored in an extra storage. It has the same type of `AssignStack::assign`.
-                let model = asg.extend_model(cdb, elim.eliminated_lits());
-                #[cfg(debug)]
-                {
-                    if let Some(cid) = cdb.validate(&model, true) {
-                        panic!(
-                            "Level {} generated assignment({:?}) falsifies {}:{:?}",
-                            asg.decision_level(),
-                            cdb.validate(&model, false).is_none(),
-                            cid,
-                            "asg.dump(&cdb[cid])",
-                        );
-                    }
+        if USE_PRE_PROCESSING_ELIMINATOR {
+            state.flush("simplifying...");
+            if elim.simplify(asg, cdb, state).is_err() {
+                // Why inconsistent? Because the CNF contains a conflict, not an error!
+                // Or out of memory.
+                state.progress(asg, cdb, elim, rst);
+                if cdb.check_size().is_err() {
+                    return Err(SolverError::OutOfMemory);
                 }
-
-                // Run validator on the extended model.
-                if cdb.validate(&model, false).is_some() {
-                    return Err(SolverError::SolverBug);
+                return Ok(Certificate::UNSAT);
+            }
+            for vi in 1..=asg.num_vars {
+                if asg.assign(vi).is_some() || asg.var(vi).is(Flag::ELIMINATED) {
+                    continue;
                 }
+                match elim.stats(vi) {
+                    Some((_, 0)) => (),
+                    Some((0, _)) => (),
+                    Some((p, m)) if m * 10 < p => asg.var_mut(vi).turn_on(Flag::PHASE),
+                    Some((p, m)) if p * 10 < m => asg.var_mut(vi).turn_off(Flag::PHASE),
+                    _ => (),
+                }
+            }
+            asg.initialize_reward(elim.sorted_iterator());
+            asg.rebuild_order();
+        }
+        elim.stop(asg, cdb);
+    }
 
-                // map `Option<bool>` to `i32`, and remove the dummy var at the head.
-                let vals = asg
-                    .var_iter()
-                    .skip(1)
-                    .map(|v| i32::from(Lit::from((v.index, model[v.index]))))
-                    .collect::<Vec<i32>>();
+    if state.interrupt.load(Ordering::Relaxed) || limits.is_exhausted(asg, state) {
+        return Ok(Certificate::UNKNOWN);
+    }
 
-                // As a preparation for incremental solving, turn flags off.
-                for v in asg.var_iter_mut().skip(1) {
-                    if v.is(Flag::ELIMINATED) {
-                        v.turn_off(Flag::ELIMINATED);
-                    }
+    //
+    //## Search
+    //
+    state.progress(asg, cdb, elim, rst);
+    let answer = search(asg, cdb, elim, rst, state, &limits);
+    state.progress(asg, cdb, elim, rst);
+    match answer {
+        Ok(Some(true)) => {
+            // As a preparation for incremental solving, we need to backtrack to the
+            // root level. So all assignments, including assignments to eliminated vars,
+            // are stored in an extra storage. It has the same type of `AssignStack::assign`.
+            let model = asg.extend_model(cdb, elim.eliminated_lits());
+            #[cfg(debug)]
+            {
+                if let Some(cid) = cdb.validate(&model, true) {
+                    panic!(
+                        "Level {} generated assignment({:?}) falsifies {}:{:?}",
+                        asg.decision_level(),
+                        cdb.validate(&model, false).is_none(),
+                        cid,
+                        "asg.dump(&cdb[cid])",
+                    );
                 }
-                RESTART!(asg, rst);
-                Ok(Certificate::SAT(vals))
-            }
-            Ok(false) | Err(SolverError::NullLearnt) => {
-                RESTART!(asg, rst);
-                Ok(Certificate::UNSAT)
             }
-            Err(e) => {
-                RESTART!(asg, rst);
-                Err(e)
+
+            // Run validator on the extended model.
+            if cdb.validate(&model, false).is_some() {
+                return Err(SolverError::SolverBug);
             }
+
+            // map `Option<bool>` to `i32`, and remove the dummy var at the head
+            // and the vars of activation literals.
+            let vals = asg
+                .var_iter()
+                .skip(1)
+                .filter(|v| state.scope_vars.binary_search(&v.index).is_err())
+                .map(|v| i32::from(Lit::from((v.index, model[v.index]))))
+                .collect::<Vec<i32>>();
+
+            RESTART!(asg, rst);
+            Ok(Certificate::SAT(vals))
+        }
+        Ok(Some(false)) | Err(SolverError::NullLearnt) => {
+            RESTART!(asg, rst);
+            Ok(Certificate::UNSAT)
+        }
+        Ok(None) => {
+            RESTART!(asg, rst);
+            Ok(Certificate::UNKNOWN)
+        }
+        Err(e) => {
+            RESTART!(asg, rst);
+            Err(e)
         }
     }
 }
 
-/// main loop; returns `Ok(true)` for SAT, `Ok(false)` for UNSAT.
+/// main loop; returns `Ok(Some(true))` for SAT, `Ok(Some(false))` for UNSAT,
+/// and `Ok(None)` if a budget or the time limit is exhausted, or it's stopped by a request.
 fn search(
     asg: &mut AssignStack,
     cdb: &mut ClauseDB,
     elim: &mut Eliminator,
     rst: &mut Restarter,
     state: &mut State,
-) -> Result<bool, SolverError> {
+    limits: &Limits,
+) -> Result<Option<bool>, SolverError> {
     let mut a_decision_was_made = false;
     let use_vivify = state.config.use_vivify();
+    let use_mld = state.config.use_mld();
+    let use_probe = state.config.use_probe();
+    let use_equiv = state.config.use_equiv();
     rst.update(ProgressUpdate::Luby);
     rst.update(ProgressUpdate::Remain(asg.num_vars - asg.num_asserted_vars));
 
     loop {
+        if state.interrupt.load(Ordering::Relaxed) || limits.is_exhausted(asg, state) {
+            return Ok(None);
+        }
         asg.reward_update();
         let ci = asg.propagate(cdb);
         if ci.is_none() {
             state.last_asg = state.last_asg.max(asg.stack_len());
-            if asg.num_vars <= state.last_asg + asg.num_eliminated_vars {
-                return Ok(true);
+            if asg.num_vars <= state.last_asg + asg.num_eliminated_vars
+                && state.assumptions.len() <= asg.decision_level() as usize
+            {
+                let lemmas = asg.check_model_external()?;
+                if lemmas.is_empty() {
+                    return Ok(Some(true));
+                }
+                // The model was rejected by the external propagator.
+                RESTART!(asg, rst);
+                state.last_asg = 0;
+                for lemma in lemmas.iter() {
+                    if add_clause_at_root(asg, cdb, elim, lemma, false).is_err() {
+                        return Ok(Some(false));
+                    }
+                }
+                continue;
             }
         } else {
             if 0 < state.last_asg {
@@ -222,8 +543,10 @@ fn search(
                 state.last_asg = 0;
             }
             if asg.decision_level() == asg.root_level {
-                analyze_final(asg, state, &cdb[ci]);
-                return Ok(false);
+                return Ok(Some(false));
+            }
+            if state.terminate.call(|f| f()) == Some(true) {
+                return Ok(None);
             }
             handle_conflict(asg, cdb, elim, rst, state, ci)?;
             rst.update(ProgressUpdate::Remain(asg.var_stats().3));
@@ -233,6 +556,7 @@ fn search(
             }
             #[allow(unused_variables)]
             if let Some((parity, new_cycle)) = rst.stabilize(asg.num_conflict) {
+                asg.handle(SolverEvent::Stabilize((parity, new_cycle)));
                 if new_cycle {
                     let v = asg.var_stats();
                     let r = rst.exports();
@@ -274,6 +598,22 @@ fn search(
                     }
                 }
             }
+            if let Some(target) = asg.next_rephase_target() {
+                let mut found = false;
+                if target == RephaseTarget::Walk {
+                    RESTART!(asg, rst);
+                    match local_search(asg, cdb, state, limits) {
+                        None => return Ok(None),
+                        Some(b) => found = b,
+                    }
+                }
+                asg.rephase(target);
+                if found {
+                    // The next propagation ends with the model.
+                    decide_phases(asg, state);
+                }
+                state.log(asg.num_conflict, format!("Rephase: {}", target));
+            }
             if a_decision_was_made {
                 a_decision_was_made = false;
             } else {
@@ -287,7 +627,7 @@ fn search(
                 adapt_modules(asg, cdb, elim, rst, state)?;
                 if let Some(p) = state.elapsed() {
                     if 1.0 <= p {
-                        return Err(SolverError::TimeOut);
+                        return Ok(None);
                     }
                 } else {
                     return Err(SolverError::UndescribedError);
@@ -296,21 +636,39 @@ fn search(
         }
         // Simplification has been postponed because chronoBT was used.
         if asg.decision_level() == asg.root_level {
+            if import_clauses(asg, cdb, elim, state).is_err() {
+                return Ok(Some(false));
+            }
             if use_vivify && 1.0 <= state.to_vivify {
                 state.to_vivify = 0.0;
                 if vivify(asg, cdb, elim, state).is_err() {
                     // return Err(SolverError::UndescribedError);
-                    analyze_final(asg, state, &cdb[ci]);
-                    return Ok(false);
+                    return Ok(Some(false));
                 }
             }
             // `elim.to_simplify` is increased much in particular when vars are asserted or
             // learnts are small. We don't need to count the number of asserted vars.
             if state.config.c_ip_int <= elim.to_simplify as usize {
                 elim.to_simplify = 0.0;
+                if use_probe {
+                    match processor::probe(asg, cdb, state) {
+                        Ok((0, 0)) => (),
+                        Ok((failed, lifted)) => state.log(
+                            asg.num_conflict,
+                            format!("Probe: failed:{}, lifted:{}", failed, lifted),
+                        ),
+                        Err(_) => return Ok(Some(false)),
+                    }
+                }
+                if use_equiv {
+                    match processor::substitute(asg, cdb, elim) {
+                        Ok(0) => (),
+                        Ok(n) => state.log(asg.num_conflict, format!("Equiv: substituted:{}", n)),
+                        Err(_) => return Ok(Some(false)),
+                    }
+                }
                 if elim.enable {
-                    #[cfg(feature = "progress_MLD")]
-                    {
+                    if use_mld {
                         elim.subsume_literal_limit = (rst.mld.get_slow() * 2.0) as usize;
                     }
                     elim.activate();
@@ -323,7 +681,32 @@ fn search(
             }
         }
         if !asg.remains() {
-            let lit = asg.select_decision_literal();
+            //
+            //## Assumptions are decided before any other var
+            //
+            let mut assumption = None;
+            while (asg.decision_level() as usize) < state.assumptions.len() {
+                let a = state.assumptions[asg.decision_level() as usize];
+                match asg.assigned(a) {
+                    Some(true) => asg.level_up(),
+                    Some(false) => {
+                        asg.explain_external(cdb);
+                        analyze_final(asg, cdb, state, a);
+                        return Ok(Some(false));
+                    }
+                    None => {
+                        assumption = Some(a);
+                        break;
+                    }
+                }
+            }
+            let lit = match assumption {
+                Some(a) => a,
+                None if asg.num_vars <= asg.stack_len() + asg.num_eliminated_vars => continue,
+                None => asg
+                    .decide_external()
+                    .unwrap_or_else(|| asg.select_decision_literal()),
+            };
             asg.assign_by_decision(lit);
             state[Stat::Decision] += 1;
             a_decision_was_made = true;
@@ -363,33 +746,149 @@ fn adapt_modules(
     Ok(())
 }
 
-fn analyze_final(asg: &mut AssignStack, state: &mut State, c: &Clause) {
-    let mut seen = vec![false; asg.num_vars + 1];
-    state.conflicts.clear();
-    if asg.decision_level() == 0 {
+/// set the phases for `RephaseTarget::Walk` to the best assignment found by stochastic
+/// local search from the best phases. Return `Some(true)` if it satisfies the problem,
+/// and `None` if it's stopped by a request or a budget before the search.
+fn local_search(
+    asg: &mut AssignStack,
+    cdb: &ClauseDB,
+    state: &mut State,
+    limits: &Limits,
+) -> Option<bool> {
+    debug_assert_eq!(asg.decision_level(), asg.root_level);
+    if state.interrupt.load(Ordering::Relaxed)
+        || limits.is_exhausted(asg, state)
+        || state.terminate.call(|f| f()) == Some(true)
+    {
+        return None;
+    }
+    let mut phases = asg.best_phases_ref().clone();
+    let (start, best) = cdb.stochastic_local_search(asg, &mut phases, state.config.sls_flips);
+    asg.set_walk_phases(phases);
+    state.log(
+        asg.num_conflict,
+        format!("LocalSearch: falsified {:>9} -> {:>9}", start, best),
+    );
+    Some(best == 0)
+}
+
+/// decide all unassigned vars by their phases, the assumptions first, unless the phases
+/// falsify an assumption.
+fn decide_phases(asg: &mut AssignStack, state: &mut State) {
+    debug_assert_eq!(asg.decision_level(), asg.root_level);
+    if state.assumptions.iter().any(|a| match asg.assigned(*a) {
+        Some(b) => !b,
+        None => asg.var(a.vi()).is(Flag::PHASE) != bool::from(*a),
+    }) {
         return;
     }
-    for l in &c.lits {
-        let vi = l.vi();
-        if 0 < asg.level(vi) {
-            asg.var_mut(vi).turn_on(Flag::CA_SEEN);
+    let mut num_decision = 0;
+    for a in state.assumptions.iter() {
+        if asg.assigned(*a) == Some(true) {
+            asg.level_up();
+        } else {
+            asg.assign_by_decision(*a);
+            num_decision += 1;
         }
     }
-    let end = if asg.decision_level() <= asg.root_level {
-        asg.stack_len()
-    } else {
-        asg.len_upto(asg.root_level)
-    };
-    for l in asg.stack_range(asg.len_upto(0)..end) {
+    for vi in 1..=asg.num_vars {
+        if asg.assign(vi).is_none() && !asg.var(vi).is(Flag::ELIMINATED) {
+            asg.assign_by_decision(Lit::from_assign(vi, asg.var(vi).is(Flag::PHASE)));
+            num_decision += 1;
+        }
+    }
+    state[Stat::Decision] += num_decision;
+}
+
+/// add the clauses given via import handles as learnt clauses at root level.
+fn import_clauses(
+    asg: &mut AssignStack,
+    cdb: &mut ClauseDB,
+    elim: &mut Eliminator,
+    state: &mut State,
+) -> MaybeInconsistent {
+    let clauses = std::mem::take(&mut *state.imports.lock().expect("an import handle panicked"));
+    // They aren't derived in the proof, so no checker can follow them.
+    if cdb.certified.is_active() {
+        return Ok(());
+    }
+    for clause in clauses.iter() {
+        add_clause_at_root(asg, cdb, elim, clause, true)?;
+    }
+    Ok(())
+}
+
+/// add a clause at root level after removing falsified literals.
+/// Clauses containing an unknown or eliminated var are dropped.
+fn add_clause_at_root(
+    asg: &mut AssignStack,
+    cdb: &mut ClauseDB,
+    elim: &mut Eliminator,
+    clause: &[i32],
+    learnt: bool,
+) -> MaybeInconsistent {
+    let mut lits: Vec<Lit> = Vec::new();
+    for i in clause.iter() {
+        let vi = i.unsigned_abs() as usize;
+        if *i == 0 || asg.num_vars < vi || asg.var(vi).is(Flag::ELIMINATED) {
+            return Ok(());
+        }
+        let l = Lit::from(*i);
+        match asg.assigned(l) {
+            Some(true) => return Ok(()),
+            Some(false) => (),
+            None if lits.contains(&!l) => return Ok(()),
+            None if !lits.contains(&l) => lits.push(l),
+            None => (),
+        }
+    }
+    match lits.len() {
+        0 => return Err(SolverError::Inconsistent),
+        1 => {
+            cdb.certificate_add(&lits, &[]);
+            asg.assign_at_root_level(lits[0])?;
+        }
+        _ => {
+            let cid = cdb.new_clause(asg, &mut lits, learnt, true, &[]);
+            elim.add_cid_occur(asg, cid, &mut cdb[cid], true);
+        }
+    }
+    Ok(())
+}
+
+/// collect the assumptions responsible for falsifying an assumption `p`.
+/// `state.conflicts` holds their negations as a clause.
+fn analyze_final(asg: &AssignStack, cdb: &ClauseDB, state: &mut State, p: Lit) {
+    state.conflicts.clear();
+    state.conflicts.push(!p);
+    if asg.level(p.vi()) == 0 {
+        return;
+    }
+    let mut seen = vec![false; asg.num_vars + 1];
+    seen[p.vi()] = true;
+    for l in asg
+        .stack_range(asg.len_upto(0)..asg.stack_len())
+        .iter()
+        .rev()
+    {
         let vi = l.vi();
-        if seen[vi] {
-            if asg.reason(vi) == AssignReason::default() {
+        if !seen[vi] {
+            continue;
+        }
+        match asg.reason(vi) {
+            AssignReason::None => {
+                debug_assert!(0 < asg.level(vi));
                 state.conflicts.push(!*l);
-            } else {
-                let level = asg.level_ref();
-                for l in &c[(c.len() != 2) as usize..] {
-                    let vj = l.vi();
-                    if 0 < level[vj] {
+            }
+            AssignReason::Implication(_, lit) if lit != NULL_LIT => {
+                if 0 < asg.level(lit.vi()) {
+                    seen[lit.vi()] = true;
+                }
+            }
+            AssignReason::Implication(cid, _) => {
+                for lit in cdb[cid].iter() {
+                    let vj = lit.vi();
+                    if vj != vi && 0 < asg.level(vj) {
                         seen[vj] = true;
                     }
                 }
@@ -64,8 +64,12 @@ pub fn vivify(
             c.turn_off(Flag::DERIVE20);
         }
         let clits = c.lits.clone();
+        let proof_id = c.proof_id;
         let mut copied: Vec<Lit> = Vec::new();
         let mut flipped = true;
+        // the antecedents found by Rule 3, which are required by LRAT
+        let mut analyzed = false;
+        let mut conflict_hints: Option<Vec<usize>> = None;
         // elim.eliminate_satisfied_clauses(asg, cdb, false);
         num_check += 1;
         'this_clause: for l in clits.iter() {
@@ -111,7 +115,7 @@ pub fn vivify(
                         _ => {
                             cdb.handle(SolverEvent::Vivify(true));
                             debug_assert!(cdb.during_vivification);
-                            let cid = cdb.new_clause(asg, &mut copied.clone(), true, false);
+                            let cid = cdb.new_clause(asg, &mut copied.clone(), true, false, &[]);
                             cdb.handle(SolverEvent::Vivify(false));
                             Some(cid)
                         }
@@ -123,6 +127,10 @@ pub fn vivify(
                     if !cc.is_none() {
                         copied.push(!*l);
                         copied = asg.analyze(cdb, &copied, &cdb[cc].lits, &mut seen);
+                        analyzed = true;
+                        if cdb.needs_hints() {
+                            conflict_hints = cdb.derive_hints(asg, &copied, cc);
+                        }
                         // this reverts dda678e
                         // Here we found an inconsistency.
                         // So we can abort this function without rolling back to level zero.
@@ -150,9 +158,23 @@ pub fn vivify(
         if flipped {
             flip(&mut copied);
         }
+        let hints: Option<Vec<usize>> = if !cdb.needs_hints() {
+            Some(Vec::new())
+        } else if analyzed {
+            conflict_hints
+        } else {
+            // Rule 1 dropped literals falsified by unit clauses.
+            let mut v = clits
+                .iter()
+                .filter(|l| !copied.contains(l) && asg.assigned(**l) == Some(false))
+                .map(|l| cdb.unit_proof_id(!*l))
+                .collect::<Vec<_>>();
+            v.push(proof_id);
+            Some(v)
+        };
         match copied.len() {
             0 if flipped => {
-                cdb.certificate_add(&clits[0..1]);
+                cdb.certificate_add(&clits[0..1], &hints.unwrap_or_default());
                 debug_assert!(asg.stack_iter().all(|l| asg.assigned(*l).is_some()));
                 return Err(SolverError::Inconsistent);
             }
@@ -163,6 +185,8 @@ pub fn vivify(
                     num_purge += 1;
                 }
             }
+            // It can't be certified in LRAT.
+            _ if hints.is_none() => (),
             1 => {
                 let l0 = copied[0];
                 debug_assert_ne!(asg.assigned(l0), Some(false));
@@ -170,7 +194,7 @@ pub fn vivify(
                 if asg.assigned(l0) == None {
                     num_assert += 1;
                     check_thr = ((check_thr as f64 * 1.2) as usize).min(check_max);
-                    cdb.certificate_add(&copied);
+                    cdb.certificate_add(&copied, hints.as_deref().unwrap_or_default());
                     asg.assign_at_root_level(l0)?;
                     if !asg.propagate(cdb).is_none() {
                         // panic!("Vivification found an inconsistency.");
@@ -191,10 +215,14 @@ pub fn vivify(
                 } else {
                     num_shrink += 1;
                     check_thr = (check_thr + state.vivify_thr / 10).min(check_max);
-                    cdb.certificate_add(&copied);
-                    cdb.handle(SolverEvent::Vivify(true));
-                    let cj = cdb.new_clause(asg, &mut copied, is_learnt, true);
-                    cdb.handle(SolverEvent::Vivify(false));
+                    // This is not a temporal clause; it should be certified and deleted later.
+                    let cj = cdb.new_clause(
+                        asg,
+                        &mut copied,
+                        is_learnt,
+                        true,
+                        hints.as_deref().unwrap_or_default(),
+                    );
                     cdb[cj].turn_on(Flag::VIVIFIED);
                     elim.to_simplify += 1.0 / ((n - 1) as f64).powf(1.6);
                     debug_assert!(!cdb[ci].is(Flag::DEAD));
@@ -4,20 +4,21 @@ use {crate::cdb::ClauseDBIF, std::cmp::Ordering};
 use {
     crate::{
         assign::{AssignIF, VarSelectIF},
-        solver::{RestartIF, RestartMode, RestarterEMAs, SolverEvent},
+        solver::{Budget, RestartIF, RestartMode, RestarterEMAs, SolverEvent},
         types::*,
     },
     std::{
         fmt,
         io::{stdout, Write},
         ops::{Index, IndexMut},
+        sync::{atomic::AtomicBool, Arc, Mutex},
         time::{Duration, Instant},
     },
 };
 #[cfg(not(feature = "strategy_adaptation"))]
-const PROGRESS_REPORT_ROWS: usize = 8;
-#[cfg(feature = "strategy_adaptation")]
 const PROGRESS_REPORT_ROWS: usize = 9;
+#[cfg(feature = "strategy_adaptation")]
+const PROGRESS_REPORT_ROWS: usize = 10;
 
 /// API for state/statistics management, providing [`progress`](`crate::state::StateIF::progress`).
 pub trait StateIF {
@@ -185,6 +186,54 @@ impl IndexMut<Stat> for [usize] {
     }
 }
 
+/// A user-supplied callback, which is shared by the clones of a solver.
+pub struct Hook<F>(Option<Arc<Mutex<F>>>);
+
+impl<F> Default for Hook<F> {
+    fn default() -> Self {
+        Hook(None)
+    }
+}
+
+impl<F> Clone for Hook<F> {
+    fn clone(&self) -> Self {
+        Hook(self.0.clone())
+    }
+}
+
+impl<F> fmt::Debug for Hook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Hook({})",
+            if self.0.is_some() { "set" } else { "unset" }
+        )
+    }
+}
+
+impl<F> Hook<F> {
+    /// make a hook from a callback; `None` for no callback.
+    pub fn new(callback: Option<F>) -> Self {
+        Hook(callback.map(|f| Arc::new(Mutex::new(f))))
+    }
+    /// return `true` if a callback is set.
+    pub fn is_some(&self) -> bool {
+        self.0.is_some()
+    }
+    /// call the callback via `call`; return `None` if no callback is set.
+    pub fn call<R>(&self, call: impl FnOnce(&mut F) -> R) -> Option<R> {
+        self.0
+            .as_ref()
+            .map(|f| call(&mut *f.lock().expect("a callback panicked")))
+    }
+}
+
+/// A callback to stop the search; it returns `true` to stop.
+pub type TerminateCallback = Box<dyn FnMut() -> bool + Send>;
+
+/// A callback to receive learnt clauses.
+pub type LearntCallback = Box<dyn FnMut(&[i32]) + Send>;
+
 /// Data storage for [`Solver`](`crate::solver::Solver`).
 #[derive(Debug, Clone)]
 pub struct State {
@@ -212,8 +261,16 @@ pub struct State {
     pub b_lvl: Ema,
     /// EMA of conflicting levels
     pub c_lvl: Ema,
+    /// assumptions used in the current solving, decided before any other var
+    pub assumptions: Vec<Lit>,
     /// hold conflicting literals for UNSAT problems
     pub conflicts: Vec<Lit>,
+    /// `true` if the problem is refuted without assumptions; it stays UNSAT
+    pub inconsistent: bool,
+    /// activation literals of the constraint scopes opened by `push`
+    pub scopes: Vec<Lit>,
+    /// vars taken by activation literals in ascending order, which are hidden in models
+    pub scope_vars: Vec<VarId>,
     /// hold the previous number of non-conflicting assignment
     pub last_asg: usize,
     /// working place to build learnt clauses
@@ -228,6 +285,20 @@ pub struct State {
     pub start: Instant,
     /// upper limit for timeout handling
     pub time_limit: f64,
+    /// a flag to stop the search, shared with interrupt handles
+    pub interrupt: Arc<AtomicBool>,
+    /// a callback to stop the search, polled at every conflict
+    pub terminate: Hook<TerminateCallback>,
+    /// a callback to receive learnt clauses
+    pub learnt_hook: Hook<LearntCallback>,
+    /// the maximum length of learnt clauses passed to `learnt_hook`
+    pub learnt_hook_len: usize,
+    /// the maximum LBD of learnt clauses passed to `learnt_hook`
+    pub learnt_hook_lbd: usize,
+    /// clauses to be added at the next restart, shared with import handles
+    pub imports: Arc<Mutex<Vec<Vec<i32>>>>,
+    /// resource limits on the current invocation
+    pub budget: Budget,
     /// logging facility.
     log_messages: Vec<String>,
 }
@@ -247,7 +318,11 @@ impl Default for State {
             vivify_thr: 0,
             b_lvl: Ema::new(5_000),
             c_lvl: Ema::new(5_000),
+            assumptions: Vec::new(),
             conflicts: Vec::new(),
+            inconsistent: false,
+            scopes: Vec::new(),
+            scope_vars: Vec::new(),
             last_asg: 0,
             new_learnt: Vec::new(),
             derive20: Vec::new(),
@@ -255,6 +330,13 @@ impl Default for State {
             record: ProgressRecord::default(),
             start: Instant::now(),
             time_limit: 0.0,
+            interrupt: Arc::new(AtomicBool::new(false)),
+            terminate: Hook::default(),
+            learnt_hook: Hook::default(),
+            learnt_hook_len: 0,
+            learnt_hook_lbd: 0,
+            imports: Arc::new(Mutex::new(Vec::new())),
+            budget: Budget::default(),
             log_messages: Vec::new(),
         }
     }
@@ -499,17 +581,15 @@ impl StateIF for State {
 
         let (elim_num_full, _num_sat, _elim_to_simplify) = elim.exports();
 
+        let (phase_target, phase_num_rephase, phase_num_target, phase_num_best) = asg.phase_stats();
+
         let rst_mode = rst.mode().0;
 
         let (rst_num_blk, rst_num_rst, rst_num_span, rst_num_cycle, _stb_lspan) = rst.exports();
         // let rst_num_stb = rst.mode().1;
 
-        #[cfg(not(feature = "progress_ACC"))]
         let (rst_asg, rst_lbd) = *rst.exports_box();
 
-        #[cfg(feature = "progress_ACC")]
-        let (_rst_acc, rst_asg, rst_lbd) = *rst.exports_box();
-
         if self.config.use_log {
             self.dump(asg, cdb, rst);
             return;
@@ -585,6 +665,8 @@ impl StateIF for State {
                 RestartMode::Dynamic => "    Restart",
                 RestartMode::Luby if self.config.no_color => "LubyRestart",
                 RestartMode::Luby => "\x1B[001m\x1B[035mLubyRestart\x1B[000m",
+                RestartMode::Geometric if self.config.no_color => " GeoRestart",
+                RestartMode::Geometric => " \x1B[001m\x1B[035mGeoRestart\x1B[000m",
                 RestartMode::Stabilize if self.config.no_color => "  Stabilize",
                 RestartMode::Stabilize => "  \x1B[001m\x1B[030mStabilize\x1B[000m",
             },
@@ -603,6 +685,13 @@ impl StateIF for State {
             im!("{:>9}", self, LogUsizeId::LubySpan, rst_num_span),
         );
         self[LogUsizeId::LubyCycle] = rst_num_cycle;
+        println!(
+            "\x1B[2K       Phase|mode:{:>9}, #rph:{}, tgt#:{}, bst#:{}",
+            phase_target,
+            im!("{:>9}", self, LogUsizeId::Rephase, phase_num_rephase),
+            im!("{:>9}", self, LogUsizeId::TargetAssign, phase_num_target),
+            im!("{:>9}", self, LogUsizeId::BestAssign, phase_num_best),
+        );
         println!(
             "\x1B[2K         EMA|tLBD:{}, tASG:{}, core:{}, /dpc:{}",
             fm!("{:>9.4}", self, LogF64Id::TrendLBD, rst_lbd.trend()),
@@ -809,12 +898,8 @@ impl State {
             e.0 + e.2
         };
 
-        #[cfg(not(feature = "progress_ACC"))]
         let (rst_asg, rst_lbd) = *rst.exports_box();
 
-        #[cfg(feature = "progress_ACC")]
-        let (_, rst_asg, rst_lbd) = *rst.exports_box();
-
         println!(
             "{:>3},{:>7},{:>7},{:>7},{:>6.3},,{:>7},{:>7},\
              {:>7},,{:>5},{:>5},{:>6.2},{:>6.2},,{:>7.2},{:>8.2},{:>8.2},,\
@@ -876,6 +961,13 @@ pub enum LogUsizeId {
     RestartCancel,
     RestartStabilize,
 
+    //
+    //## rephasing
+    //
+    Rephase,
+    TargetAssign,
+    BestAssign,
+
     //
     //## pre-in processor
     //
@@ -474,6 +474,37 @@ impl Ema2 {
     }
 }
 
+/// A tiny xorshift PRNG, which is enough to pick clauses, vars and phases.
+#[derive(Debug, Clone, Copy)]
+pub struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    pub fn new(seed: u64) -> Self {
+        Xorshift { state: seed.max(1) }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+    /// return a number in `0..n`.
+    pub fn next_usize(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    /// return a number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
 /// Internal errors.
 /// Note: returning `Result<(), a-singleton>` is identical to returning `bool`.
 #[derive(Debug, Eq, PartialEq)]
@@ -486,6 +517,8 @@ pub enum SolverError {
     OutOfMemory,
     OutOfRange,
     TimeOut,
+    /// a proof can't be made with an external propagator
+    ProofUnavailable,
     SolverBug,
     UndescribedError,
 }
@@ -720,6 +753,12 @@ bitflags! {
         const CA_SEEN      = 0b0000_0010_0000_0000;
         /// the previous assigned value of a Var.
         const PHASE        = 0b0000_0100_0000_0000;
+        /// a var has a literal witnessing clauses removed by blocked clause elimination.
+        const BLOCKING     = 0b0000_1000_0000_0000;
+        /// a var is used as an assumption in the current solving.
+        const ASSUMED      = 0b0001_0000_0000_0000;
+        /// a var is frozen and never eliminated.
+        const FROZEN       = 0b1000_0000_0000_0000;
     }
 }
 
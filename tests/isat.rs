@@ -89,6 +89,10 @@ fn run(cnf: &str, assigns: &[i32], switch: bool) -> usize {
                 println!("s UNSATISFIABLE");
                 break;
             }
+            Ok(Certificate::UNKNOWN) => {
+                println!("s UNKNOWN");
+                break;
+            }
             Err(e) => {
                 println!("s UNKNOWN; {}", e);
                 break;
@@ -1,6 +1,5 @@
 use std::{
-    env,
-    fs::{self, File},
+    env, fs,
     path::PathBuf,
     process::{Command, Stdio},
 };
@@ -13,9 +12,7 @@ for f in ${UUF250}*.cnf; do
     if [ -z ${target}.out ]; then
         echo ' FAIL TO CERTIFICATE: ${f}'
         exit 1;
-    egrep -v '^[cs]' < ${target}.out > ${target}.drat
-    gratgen ${f} ${target}.drat -o ${target}.grat -j 4 > /dev/null
-    gratchk unsat ${f} ${target}.grat
+    dmcr -C -p ${target}.out ${f}
 */
 
 fn main() {
@@ -25,8 +22,6 @@ fn main() {
             if let Ok(cnf) = e.map(|e| e.path()) {
                 if let Some(target) = cnf.file_name().map(PathBuf::from) {
                     let out = target.with_extension("out");
-                    let drat = target.with_extension("drat");
-                    let grat = target.with_extension("grat");
                     // println!("########################################");
                     print!("# {}", cnf.file_name().unwrap().to_string_lossy());
                     // rm -f ${target}.out
@@ -35,7 +30,7 @@ fn main() {
                     }
                     // splr -c -p ${target}.out ${f} > /dev/null
                     Command::new("splr")
-                        .args(&["-c", "-p", &*out.to_string_lossy(), &*cnf.to_string_lossy()])
+                        .args(["-c", "-p", &*out.to_string_lossy(), &*cnf.to_string_lossy()])
                         .stdout(Stdio::null())
                         .output()
                         .expect("failed to execute Splr");
@@ -50,52 +45,17 @@ fn main() {
                             out.to_string_lossy(),
                         ));
                     }
-                    // egrep -v '^[cs]' < ${target}.out > ${target}.drat
-                    Command::new("egrep")
-                        .args(&["-v", "^[cs]"])
-                        .stdin(File::open(out).expect(""))
-                        .stdout(File::create(&drat).expect(""))
-                        .output()
-                        .expect("");
-                    // gratgen ${f} ${target}.drat -o ${target}.grat -j 4 > /dev/null
-                    Command::new("gratgen")
-                        .args(&[
-                            &*cnf.to_string_lossy(),
-                            &*drat.to_string_lossy(),
-                            "-o",
-                            &*grat.to_string_lossy(),
-                            "-j",
-                            "4",
-                        ])
-                        .stdin(Stdio::piped())
-                        .stdout(Stdio::null())
-                        .stderr(Stdio::null())
-                        .output()
-                        .unwrap();
-                    // .expect(&format!("FAIL TO GENERATE: {} => {}",
-                    //                 cnf.file_name().unwrap().to_string_lossy(),
-                    //                 grat.to_string_lossy(),
-                    // ));
-                    // gratchk unsat ${f} ${target}.grat
-                    if grat.exists() {
-                        print!(" => {}", grat.to_string_lossy());
-                    } else {
-                        panic!(format!(
-                            " FAIL TO CONVERT: {} => {}",
-                            cnf.file_name().unwrap().to_string_lossy(),
-                            grat.to_string_lossy(),
-                        ));
-                    }
+                    // dmcr -C -p ${target}.out ${f}
                     let mut pass = false;
-                    if let Ok(out) = Command::new("gratchk")
-                        .args(&["unsat", &*cnf.to_string_lossy(), &*grat.to_string_lossy()])
+                    if let Ok(res) = Command::new("dmcr")
+                        .args(["-C", "-p", &*out.to_string_lossy(), &*cnf.to_string_lossy()])
                         .stdin(Stdio::piped())
                         .stderr(Stdio::null())
                         .output()
                     {
-                        let str = String::from_utf8_lossy(&out.stdout);
+                        let str = String::from_utf8_lossy(&res.stdout);
                         for l in (*str).split('\n') {
-                            if l.contains(&"s VERIFIED UNSAT") {
+                            if l.contains("A valid proof") {
                                 pass = true;
                                 println!(" => VERIFIED UNSAT");
                                 break;
@@ -106,7 +66,7 @@ fn main() {
                         panic!(format!(
                             " FAIL TO CERTIFICATE: {} => {}",
                             cnf.file_name().unwrap().to_string_lossy(),
-                            grat.to_string_lossy(),
+                            out.to_string_lossy(),
                         ));
                     }
                 }